@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Representation of air quality, concentrations of pollutants are in µg/m³
+///
+/// Providers rate air quality by different scales, so the index is calculated from concentrations
+/// by the scale of US EPA (0-500, breakpoints of 2024), instant concentrations are taken
+/// instead of the averages over 1, 8 or 24 hours required by the scale
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct AirQuality {
+    /// Air quality index by the scale of US EPA, none => there are no concentrations
+    pub aqi: Option<u32>,
+    pub category: Option<AqiCategory>,
+    /// Fine particles (diameter <= 2.5 µm)
+    pub pm2_5: Option<f64>,
+    /// Coarse particles (diameter <= 10 µm)
+    pub pm10: Option<f64>,
+    /// Ozone
+    pub o3: Option<f64>,
+    /// Nitrogen dioxide
+    pub no2: Option<f64>,
+    /// Sulphur dioxide
+    pub so2: Option<f64>,
+    /// Carbon monoxide
+    pub co: Option<f64>,
+}
+
+/// Category of air quality index by the scale of US EPA
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AqiCategory {
+    /// 0-50
+    Good,
+    /// 51-100
+    Moderate,
+    /// 101-150
+    UnhealthyForSensitiveGroups,
+    /// 151-200
+    Unhealthy,
+    /// 201-300
+    VeryUnhealthy,
+    /// 301-500
+    Hazardous,
+}
+
+/// Breakpoints of the scale as (concentration low, concentration high, index low, index high)
+/// for each category
+type Breakpoints = [(f64, f64, f64, f64); 6];
+
+/// PM2.5 in µg/m³ (24 hours), truncated to 0.1
+const PM2_5_BREAKPOINTS: Breakpoints = [
+    (0.0, 9.0, 0.0, 50.0),
+    (9.1, 35.4, 51.0, 100.0),
+    (35.5, 55.4, 101.0, 150.0),
+    (55.5, 125.4, 151.0, 200.0),
+    (125.5, 225.4, 201.0, 300.0),
+    (225.5, 325.4, 301.0, 500.0),
+];
+
+/// PM10 in µg/m³ (24 hours), truncated to 1
+const PM10_BREAKPOINTS: Breakpoints = [
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 154.0, 51.0, 100.0),
+    (155.0, 254.0, 101.0, 150.0),
+    (255.0, 354.0, 151.0, 200.0),
+    (355.0, 424.0, 201.0, 300.0),
+    (425.0, 604.0, 301.0, 500.0),
+];
+
+/// O3 in ppb (8 hours), truncated to 1, the highest category is defined only for 1 hour
+const O3_BREAKPOINTS: Breakpoints = [
+    (0.0, 54.0, 0.0, 50.0),
+    (55.0, 70.0, 51.0, 100.0),
+    (71.0, 85.0, 101.0, 150.0),
+    (86.0, 105.0, 151.0, 200.0),
+    (106.0, 200.0, 201.0, 300.0),
+    (405.0, 604.0, 301.0, 500.0),
+];
+
+/// NO2 in ppb (1 hour), truncated to 1
+const NO2_BREAKPOINTS: Breakpoints = [
+    (0.0, 53.0, 0.0, 50.0),
+    (54.0, 100.0, 51.0, 100.0),
+    (101.0, 360.0, 101.0, 150.0),
+    (361.0, 649.0, 151.0, 200.0),
+    (650.0, 1249.0, 201.0, 300.0),
+    (1250.0, 2049.0, 301.0, 500.0),
+];
+
+/// SO2 in ppb (1 hour), truncated to 1
+const SO2_BREAKPOINTS: Breakpoints = [
+    (0.0, 35.0, 0.0, 50.0),
+    (36.0, 75.0, 51.0, 100.0),
+    (76.0, 185.0, 101.0, 150.0),
+    (186.0, 304.0, 151.0, 200.0),
+    (305.0, 604.0, 201.0, 300.0),
+    (605.0, 1004.0, 301.0, 500.0),
+];
+
+/// CO in ppm (8 hours), truncated to 0.1
+const CO_BREAKPOINTS: Breakpoints = [
+    (0.0, 4.4, 0.0, 50.0),
+    (4.5, 9.4, 51.0, 100.0),
+    (9.5, 12.4, 101.0, 150.0),
+    (12.5, 15.4, 151.0, 200.0),
+    (15.5, 30.4, 201.0, 300.0),
+    (30.5, 50.4, 301.0, 500.0),
+];
+
+/// Molar volume of gas in litres at 25 °C and 1 atm, it converts µg/m³ to ppb
+const MOLAR_VOLUME: f64 = 24.45;
+
+impl AirQuality {
+    /// Build air quality from concentrations of pollutants in µg/m³, the index is calculated
+    pub fn from_concentrations(
+        pm2_5: Option<f64>,
+        pm10: Option<f64>,
+        o3: Option<f64>,
+        no2: Option<f64>,
+        so2: Option<f64>,
+        co: Option<f64>,
+    ) -> Self {
+        // Index of each pollutant with concentration in units and precision of the scale
+        let indexes = [
+            pm2_5.map(|pm2_5| sub_index(truncate(pm2_5, 10.0), &PM2_5_BREAKPOINTS)),
+            pm10.map(|pm10| sub_index(pm10.trunc(), &PM10_BREAKPOINTS)),
+            o3.map(|o3| sub_index(to_ppb(o3, 48.0).trunc(), &O3_BREAKPOINTS)),
+            no2.map(|no2| sub_index(to_ppb(no2, 46.01).trunc(), &NO2_BREAKPOINTS)),
+            so2.map(|so2| sub_index(to_ppb(so2, 64.07).trunc(), &SO2_BREAKPOINTS)),
+            co.map(|co| sub_index(truncate(to_ppb(co, 28.01) / 1000.0, 10.0), &CO_BREAKPOINTS)),
+        ];
+
+        // The worst pollutant determines the index
+        let aqi = indexes.into_iter().flatten().max();
+
+        AirQuality {
+            aqi,
+            category: aqi.map(AqiCategory::from_index),
+            pm2_5: pm2_5.map(round),
+            pm10: pm10.map(round),
+            o3: o3.map(round),
+            no2: no2.map(round),
+            so2: so2.map(round),
+            co: co.map(round),
+        }
+    }
+}
+
+impl AqiCategory {
+    /// Category of the index
+    pub fn from_index(aqi: u32) -> Self {
+        match aqi {
+            0..=50 => AqiCategory::Good,
+            51..=100 => AqiCategory::Moderate,
+            101..=150 => AqiCategory::UnhealthyForSensitiveGroups,
+            151..=200 => AqiCategory::Unhealthy,
+            201..=300 => AqiCategory::VeryUnhealthy,
+            _ => AqiCategory::Hazardous,
+        }
+    }
+}
+
+/// Pretty print for AirQuality
+impl Display for AirQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.aqi, self.category) {
+            (Some(aqi), Some(category)) => writeln!(f, "Air quality index: {aqi} ({category})")?,
+            _ => writeln!(f, "Air quality index: unknown")?,
+        }
+
+        let pollutants = [
+            ("PM2.5", self.pm2_5),
+            ("PM10", self.pm10),
+            ("O3", self.o3),
+            ("NO2", self.no2),
+            ("SO2", self.so2),
+            ("CO", self.co),
+        ];
+        for (name, concentration) in pollutants {
+            if let Some(concentration) = concentration {
+                writeln!(f, "{name}: {concentration} µg/m³")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Pretty print for AqiCategory
+impl Display for AqiCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AqiCategory::Good => "Good",
+            AqiCategory::Moderate => "Moderate",
+            AqiCategory::UnhealthyForSensitiveGroups => "Unhealthy for sensitive groups",
+            AqiCategory::Unhealthy => "Unhealthy",
+            AqiCategory::VeryUnhealthy => "Very unhealthy",
+            AqiCategory::Hazardous => "Hazardous",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Index of the concentration by linear interpolation within its category,
+/// concentration in a gap between categories gets the next one, beyond the scale - the highest index
+fn sub_index(concentration: f64, breakpoints: &Breakpoints) -> u32 {
+    breakpoints
+        .iter()
+        .find(|(_, high_concentration, _, _)| concentration <= *high_concentration)
+        .map_or(
+            500,
+            |(low_concentration, high_concentration, low_index, high_index)| {
+                let index = (high_index - low_index) / (high_concentration - low_concentration)
+                    * (concentration - low_concentration).max(0.0)
+                    + low_index;
+
+                index.round() as u32
+            },
+        )
+}
+
+/// Convert concentration of gas from µg/m³ to ppb by its molar mass
+fn to_ppb(concentration: f64, molar_mass: f64) -> f64 {
+    concentration * MOLAR_VOLUME / molar_mass
+}
+
+/// Truncate value to the precision (e.g. 10 => 0.1)
+fn truncate(value: f64, precision: f64) -> f64 {
+    (value * precision).trunc() / precision
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Index of the concentrations of particles, ozone and carbon monoxide
+    fn aqi(pm2_5: Option<f64>, pm10: Option<f64>, o3: Option<f64>, co: Option<f64>) -> Option<u32> {
+        AirQuality::from_concentrations(pm2_5, pm10, o3, None, None, co).aqi
+    }
+
+    #[test]
+    fn pm2_5() {
+        let cases = [
+            (0.0, 0),
+            // Upper and lower breakpoints of neighbouring categories
+            (9.0, 50),
+            (9.1, 51),
+            (35.4, 100),
+            (35.5, 101),
+            (55.4, 150),
+            (55.5, 151),
+            (125.4, 200),
+            (225.5, 301),
+            (325.4, 500),
+            // Concentration is truncated to 0.1 before the category is chosen
+            (9.05, 50),
+            // Between breakpoints
+            (20.0, 71),
+            // Above the top category
+            (400.0, 500),
+        ];
+
+        for (pm2_5, expected) in cases {
+            assert_eq!(
+                aqi(Some(pm2_5), None, None, None),
+                Some(expected),
+                "PM2.5 {pm2_5}"
+            );
+        }
+    }
+
+    #[test]
+    fn pm10() {
+        let cases = [
+            (54.0, 50),
+            (54.9, 50),
+            (55.0, 51),
+            (100.0, 73),
+            (604.0, 500),
+            (700.0, 500),
+        ];
+
+        for (pm10, expected) in cases {
+            assert_eq!(
+                aqi(None, Some(pm10), None, None),
+                Some(expected),
+                "PM10 {pm10}"
+            );
+        }
+    }
+
+    #[test]
+    fn gases_are_converted_to_ppb() {
+        let cases = [
+            // O3 of 53.99 ppb and 56.03 ppb
+            (Some(106.0), None, 49),
+            (Some(110.0), None, 54),
+            // 300 ppb of O3 are in the gap below the highest category
+            (Some(589.0), None, 301),
+            // CO of 8.7 ppm
+            (None, Some(10000.0), 93),
+        ];
+
+        for (o3, co, expected) in cases {
+            assert_eq!(
+                aqi(None, None, o3, co),
+                Some(expected),
+                "O3 {o3:?}, CO {co:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn worst_pollutant_sets_index() {
+        let air_quality =
+            AirQuality::from_concentrations(Some(5.0), Some(100.0), None, None, None, None);
+
+        assert_eq!(air_quality.aqi, Some(73));
+        assert_eq!(air_quality.category, Some(AqiCategory::Moderate));
+
+        let unknown = AirQuality::from_concentrations(None, None, None, None, None, None);
+        assert_eq!(unknown.aqi, None);
+        assert_eq!(unknown.category, None);
+    }
+
+    #[test]
+    fn categories() {
+        let cases = [
+            (0, AqiCategory::Good),
+            (50, AqiCategory::Good),
+            (51, AqiCategory::Moderate),
+            (100, AqiCategory::Moderate),
+            (101, AqiCategory::UnhealthyForSensitiveGroups),
+            (150, AqiCategory::UnhealthyForSensitiveGroups),
+            (151, AqiCategory::Unhealthy),
+            (200, AqiCategory::Unhealthy),
+            (201, AqiCategory::VeryUnhealthy),
+            (300, AqiCategory::VeryUnhealthy),
+            (301, AqiCategory::Hazardous),
+            (500, AqiCategory::Hazardous),
+        ];
+
+        for (aqi, expected) in cases {
+            assert_eq!(AqiCategory::from_index(aqi), expected, "AQI {aqi}");
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Severe weather alert issued for the location
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Alert {
+    /// Kind of the event (e.g. Flood Warning)
+    pub event: String,
+    pub severity: Severity,
+    /// Start of the event, none => it is already in effect
+    pub start: Option<DateTime<FixedOffset>>,
+    /// End of the event, none => until further notice
+    pub end: Option<DateTime<FixedOffset>>,
+    pub description: String,
+}
+
+/// Severity of the alert by Common Alerting Protocol, ordered from the lowest one
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Provider does not rate the alert
+    #[default]
+    Unknown,
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl Severity {
+    /// Parse severity of Common Alerting Protocol (case insensitive), anything else is unknown
+    pub fn parse(text: &str) -> Self {
+        match text.trim().to_lowercase().as_str() {
+            "minor" => Severity::Minor,
+            "moderate" => Severity::Moderate,
+            "severe" => Severity::Severe,
+            "extreme" => Severity::Extreme,
+            _ => Severity::Unknown,
+        }
+    }
+}
+
+impl Alert {
+    /// Check that the alert is not over at the moment
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.end.map_or(true, |end| end > now)
+    }
+
+    /// Check that both alerts are about the same event, providers often repeat them
+    pub fn is_same(&self, other: &Alert) -> bool {
+        self.event.eq_ignore_ascii_case(&other.event) && self.start == other.start
+    }
+}
+
+/// Pretty print for Alert
+impl Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format_time = |time: Option<DateTime<FixedOffset>>, default: &str| {
+            time.map_or(default.to_owned(), |time| {
+                time.format("%d.%m.%Y %H:%M UTC%:z").to_string()
+            })
+        };
+
+        writeln!(f, "{} ({:?})", self.event, self.severity)?;
+        writeln!(
+            f,
+            "From {} to {}",
+            format_time(self.start, "now"),
+            format_time(self.end, "further notice")
+        )?;
+        writeln!(f, "{}", self.description.trim())
+    }
+}
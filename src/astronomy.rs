@@ -0,0 +1,298 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+use std::fmt::Display;
+
+use crate::location::Location;
+
+/// Astronomical data of the day at the location, times are local to the location
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Astronomy {
+    /// None => the sun does not rise that day (polar day or night) or it is unknown
+    pub sunrise: Option<NaiveTime>,
+    /// None => the sun does not set that day (polar day or night) or it is unknown
+    pub sunset: Option<NaiveTime>,
+    pub moon_phase: Option<MoonPhase>,
+    /// Illuminated part of the moon disc in %
+    pub moon_illumination: Option<f64>,
+}
+
+/// Phase of the moon, ordered from the new moon through the lunar month
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MoonPhase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+/// Phases in order of the lunar month
+const MOON_PHASES: [MoonPhase; 8] = [
+    MoonPhase::NewMoon,
+    MoonPhase::WaxingCrescent,
+    MoonPhase::FirstQuarter,
+    MoonPhase::WaxingGibbous,
+    MoonPhase::FullMoon,
+    MoonPhase::WaningGibbous,
+    MoonPhase::LastQuarter,
+    MoonPhase::WaningCrescent,
+];
+
+/// Mean length of the lunar month in days
+const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// New moon of 06.01.2000 18:14 UTC in days since J2000 (01.01.2000 12:00 UTC)
+const REFERENCE_NEW_MOON: f64 = 6.26;
+
+/// Altitude of the sun center at sunrise and sunset in degrees,
+/// it is below the horizon due to refraction and the radius of the sun disc
+const SUNRISE_ALTITUDE: f64 = -0.833;
+
+/// Tilt of the Earth axis in degrees
+const OBLIQUITY: f64 = 23.4397;
+
+impl Astronomy {
+    /// Calculate astronomy data of the date at the coordinates, times are in the timezone with the offset
+    ///
+    /// Sunrise and sunset are calculated by the sunrise equation (accurate to a few minutes),
+    /// phase of the moon - by its mean lunar month since the known new moon
+    pub fn calculate(lat: f64, lon: f64, date: &NaiveDate, offset: FixedOffset) -> Self {
+        let days = days_since_j2000(date);
+        let local_time = |days| to_utc(days).map(|time| time.with_timezone(&offset).time());
+
+        let (sunrise, sunset) = match sun_times(lat, lon, days) {
+            Some((sunrise, sunset)) => (local_time(sunrise), local_time(sunset)),
+            None => (None, None),
+        };
+
+        // Moon looks the same from the whole Earth, its phase at local noon represents the day
+        let noon = days - f64::from(offset.local_minus_utc()) / 86400.0;
+        let month_part = (noon - REFERENCE_NEW_MOON).rem_euclid(SYNODIC_MONTH) / SYNODIC_MONTH;
+
+        Astronomy {
+            sunrise,
+            sunset,
+            moon_phase: Some(MOON_PHASES[(month_part * 8.0).round() as usize % 8]),
+            moon_illumination: Some(((1.0 - (TAU * month_part).cos()) / 2.0 * 100.0).round()),
+        }
+    }
+
+    /// Calculate astronomy data of the date at the location in the timezone with the UTC offset,
+    /// none => the location is not set by coordinates
+    pub fn calculate_at(
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Option<Self> {
+        match location {
+            Location::Coordinates { lat, lon } => {
+                Some(Astronomy::calculate(*lat, *lon, date, offset))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fill the data missing in the one of provider with the other one (e.g. calculated)
+    pub fn or(self, other: Astronomy) -> Self {
+        Astronomy {
+            sunrise: self.sunrise.or(other.sunrise),
+            sunset: self.sunset.or(other.sunset),
+            moon_phase: self.moon_phase.or(other.moon_phase),
+            moon_illumination: self.moon_illumination.or(other.moon_illumination),
+        }
+    }
+}
+
+impl MoonPhase {
+    /// Parse name of the phase (e.g. Waxing Crescent, case insensitive), anything else is unknown
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "new moon" => Some(MoonPhase::NewMoon),
+            "waxing crescent" => Some(MoonPhase::WaxingCrescent),
+            "first quarter" => Some(MoonPhase::FirstQuarter),
+            "waxing gibbous" => Some(MoonPhase::WaxingGibbous),
+            "full moon" => Some(MoonPhase::FullMoon),
+            "waning gibbous" => Some(MoonPhase::WaningGibbous),
+            "last quarter" | "third quarter" => Some(MoonPhase::LastQuarter),
+            "waning crescent" => Some(MoonPhase::WaningCrescent),
+            _ => None,
+        }
+    }
+}
+
+/// Pretty print for Astronomy
+impl Display for Astronomy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.sunrise, self.sunset) {
+            (None, None) => writeln!(f, "Sun does not rise or set")?,
+            (sunrise, sunset) => {
+                writeln!(f, "Sunrise: {}", format_time(sunrise))?;
+                writeln!(f, "Sunset: {}", format_time(sunset))?;
+            }
+        }
+        if let (Some(sunrise), Some(sunset)) = (self.sunrise, self.sunset) {
+            // Sunset after midnight is on the next day
+            let length = (sunset - sunrise + Duration::days(1)).num_minutes() % (24 * 60);
+            writeln!(f, "Day length: {}h {:02}m", length / 60, length % 60)?;
+        }
+        match (self.moon_phase, self.moon_illumination) {
+            (Some(phase), Some(illumination)) => {
+                writeln!(f, "Moon phase: {phase} ({illumination}% illuminated)")?
+            }
+            (Some(phase), None) => writeln!(f, "Moon phase: {phase}")?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Pretty print for MoonPhase
+impl Display for MoonPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MoonPhase::NewMoon => "New moon",
+            MoonPhase::WaxingCrescent => "Waxing crescent",
+            MoonPhase::FirstQuarter => "First quarter",
+            MoonPhase::WaxingGibbous => "Waxing gibbous",
+            MoonPhase::FullMoon => "Full moon",
+            MoonPhase::WaningGibbous => "Waning gibbous",
+            MoonPhase::LastQuarter => "Last quarter",
+            MoonPhase::WaningCrescent => "Waning crescent",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Sunrise and sunset in days since J2000, none => the sun does not cross the horizon that day
+fn sun_times(lat: f64, lon: f64, days: f64) -> Option<(f64, f64)> {
+    // Mean solar noon at the longitude
+    let noon = days - lon / 360.0;
+
+    let anomaly = (357.5291 + 0.98560028 * noon).rem_euclid(360.0);
+    let center = 1.9148 * anomaly.to_radians().sin()
+        + 0.02 * (2.0 * anomaly).to_radians().sin()
+        + 0.0003 * (3.0 * anomaly).to_radians().sin();
+    let ecliptic_lon = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+
+    // Solar noon is shifted by eccentricity of the orbit and the axis tilt
+    let transit = noon + 0.0053 * anomaly.to_radians().sin() - 0.0069 * (2.0 * ecliptic_lon).sin();
+
+    let declination = (ecliptic_lon.sin() * OBLIQUITY.to_radians().sin()).asin();
+    let lat = lat.to_radians();
+    let cos_hour_angle = (SUNRISE_ALTITUDE.to_radians().sin() - lat.sin() * declination.sin())
+        / (lat.cos() * declination.cos());
+
+    // Beyond [-1, 1] the sun is above (polar day) or below (polar night) the horizon all day
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+
+    Some((transit - half_day, transit + half_day))
+}
+
+/// Days since J2000 (01.01.2000 12:00 UTC) to noon UTC of the date
+fn days_since_j2000(date: &NaiveDate) -> f64 {
+    let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default();
+
+    (*date - j2000).num_days() as f64
+}
+
+/// Time of the moment in days since J2000, rounded to minutes
+fn to_utc(days: f64) -> Option<DateTime<Utc>> {
+    let j2000 = NaiveDateTime::from_timestamp_opt(946_728_000, 0)?;
+    let minutes = (days * 24.0 * 60.0).round() as i64;
+
+    let time = j2000.checked_add_signed(Duration::minutes(minutes))?;
+
+    Some(DateTime::from_utc(time, Utc))
+}
+
+/// Format local time of the sun event
+fn format_time(time: Option<NaiveTime>) -> String {
+    time.map_or("-".to_owned(), |time| time.format("%H:%M").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn hours(hours: i32) -> FixedOffset {
+        FixedOffset::east_opt(hours * 3600).unwrap()
+    }
+
+    /// Calculated time is within a few minutes of the expected one
+    fn assert_near(time: Option<NaiveTime>, hour: u32, min: u32) {
+        let expected = NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+        let time = time.unwrap();
+
+        assert!(
+            (time - expected).num_minutes().abs() <= 3,
+            "{time} is not near {expected}"
+        );
+    }
+
+    #[test]
+    fn kyiv() {
+        let kyiv = Location::Coordinates {
+            lat: 50.45,
+            lon: 30.52,
+        };
+
+        // First quarter is on 18.10.2026 at 16:13 UTC
+        let astronomy = Astronomy::calculate_at(&kyiv, &date(2026, 10, 18), hours(3)).unwrap();
+        assert_near(astronomy.sunrise, 7, 25);
+        assert_near(astronomy.sunset, 18, 1);
+        assert_eq!(astronomy.moon_phase, Some(MoonPhase::FirstQuarter));
+
+        // Full moon is on 26.10.2026 at 04:12 UTC, summer time is over by then
+        let astronomy = Astronomy::calculate_at(&kyiv, &date(2026, 10, 26), hours(2)).unwrap();
+        assert_near(astronomy.sunrise, 6, 38);
+        assert_near(astronomy.sunset, 16, 46);
+        assert_eq!(astronomy.moon_phase, Some(MoonPhase::FullMoon));
+        assert!(astronomy.moon_illumination.unwrap() >= 98.0);
+    }
+
+    #[test]
+    fn polar_night_and_day() {
+        let tromso = Location::Coordinates {
+            lat: 69.65,
+            lon: 18.96,
+        };
+
+        for (date, offset) in [
+            (date(2026, 12, 21), hours(1)),
+            (date(2026, 6, 21), hours(2)),
+        ] {
+            let astronomy = Astronomy::calculate_at(&tromso, &date, offset).unwrap();
+
+            assert_eq!(astronomy.sunrise, None, "{date}");
+            assert_eq!(astronomy.sunset, None, "{date}");
+            assert!(astronomy.moon_phase.is_some());
+            assert!(astronomy
+                .to_string()
+                .starts_with("Sun does not rise or set"));
+        }
+    }
+
+    #[test]
+    fn address_is_not_calculated() {
+        let location = Location::Address("Kyiv".to_owned());
+
+        assert!(Astronomy::calculate_at(&location, &date(2026, 10, 18), hours(3)).is_none());
+    }
+}
@@ -0,0 +1,811 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use eyre::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    env,
+    fs::{create_dir_all, read_dir, read_to_string, remove_file, write},
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use crate::{
+    air_quality::AirQuality,
+    alert::Alert,
+    astronomy::Astronomy,
+    date,
+    forecast::Forecast,
+    location::{Location, Place},
+    provider::{Agreement, Geocoder, Provider, ProviderError, ProviderType, WeatherProvider},
+    store::{ForecastStore, Origin, StoreError, Stored, StoredKind},
+    units::Units,
+};
+
+/// Environment variable that overrides the cache directory
+pub const CACHE_DIR_ENV_VAR: &str = "WEATHER_CACHE_DIR";
+
+/// Errors for Cache
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("Cache directory is not found, set it with {CACHE_DIR_ENV_VAR}")]
+    CacheDirNotFound,
+    #[error("Failed to read cache {path} with error '{error}'")]
+    FailedToRead { error: io::Error, path: String },
+    #[error("Failed to write cache {path} with error '{error}'")]
+    FailedToWrite { error: io::Error, path: String },
+    #[error("Failed to stringify cache entry with error '{error}'")]
+    FailedToStringify { error: serde_json::Error },
+    #[error("Failed to remove cache {path} with error '{error}'")]
+    FailedToRemove { error: io::Error, path: String },
+    #[error("Response is not cached")]
+    ResponseNotCached,
+}
+
+/// Kind of provider request, each one has its own time to live
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Endpoint {
+    Current,
+    Daily,
+    Range,
+    Hourly,
+    History,
+    Timezone,
+    Alerts,
+    AirQuality,
+    Astronomy,
+    Geocode,
+}
+
+impl Endpoint {
+    /// How long the response stays fresh
+    pub fn ttl(self) -> Duration {
+        match self {
+            Endpoint::Current | Endpoint::Alerts | Endpoint::AirQuality => Duration::minutes(10),
+            Endpoint::Hourly => Duration::hours(1),
+            Endpoint::Daily | Endpoint::Range => Duration::hours(3),
+            // Past weather changes only while its day is not over
+            Endpoint::History => Duration::days(1),
+            // Offset changes only with daylight saving time
+            Endpoint::Timezone => Duration::hours(6),
+            // Places do not move, and the sun of the date does not change
+            Endpoint::Geocode | Endpoint::Astronomy => Duration::days(30),
+        }
+    }
+}
+
+/// Directory from environment variable or the one in the user cache directory
+pub fn cache_dir() -> Result<PathBuf> {
+    let dir = env::var_os(CACHE_DIR_ENV_VAR)
+        .map(PathBuf::from)
+        .or_else(|| dirs::cache_dir().map(|dir| dir.join("weather")))
+        .ok_or(CacheError::CacheDirNotFound)?;
+
+    Ok(dir)
+}
+
+/// How requests use the cache
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum CacheMode {
+    /// Serve fresh responses from the cache, store new ones
+    #[default]
+    Enabled,
+    /// Always send request, store its response
+    Refresh,
+    /// Neither read nor write the cache
+    Disabled,
+    /// Never send requests, serve cached responses and stored forecasts regardless of their age
+    Offline,
+}
+
+/// Key of the cached response
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct CacheKey {
+    pub provider: ProviderType,
+    /// Base URL of the provider API, other servers (e.g. a local mock) give other responses
+    pub base_url: String,
+    pub endpoint: Endpoint,
+    pub location: Location,
+    /// Requested date or first date of the range, none => current weather
+    pub from: Option<NaiveDate>,
+    /// Last date of the range
+    pub to: Option<NaiveDate>,
+    /// Unit system of cached forecasts, none => response does not depend on it
+    pub units: Option<Units>,
+    /// UTC offset in seconds dates of cached forecasts are counted in,
+    /// none => response does not depend on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i32>,
+}
+
+/// Cached response with the time it was fetched
+#[derive(Serialize, Deserialize)]
+pub struct CacheEntry<T> {
+    pub key: CacheKey,
+    pub fetched_at: DateTime<Utc>,
+    pub data: T,
+}
+
+impl<T> CacheEntry<T> {
+    pub fn is_fresh(&self) -> bool {
+        Utc::now() - self.fetched_at < self.key.endpoint.ttl()
+    }
+}
+
+/// Summary of the cache content
+pub struct CacheStats {
+    pub dir: PathBuf,
+    pub entries: usize,
+    pub expired: usize,
+    /// Total size of entries in bytes
+    pub size: u64,
+}
+
+/// Storage of provider responses, one JSON file per key in the user cache directory
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Open cache in the cache directory
+    pub fn new() -> Result<Self> {
+        Ok(Cache { dir: cache_dir()? })
+    }
+
+    /// Get entry by the key regardless of its age, broken entry is treated as missing
+    pub fn get<T: DeserializeOwned>(&self, key: &CacheKey) -> Option<CacheEntry<T>> {
+        let raw_entry = read_to_string(self.path(key)).ok()?;
+
+        // Different keys can share the file in case of hash collision
+        serde_json::from_str::<CacheEntry<T>>(&raw_entry)
+            .ok()
+            .filter(|entry| entry.key == *key)
+    }
+
+    /// Store data under the key
+    pub fn put<T: Serialize>(&self, key: &CacheKey, data: &T) -> Result<()> {
+        create_dir_all(&self.dir).map_err(|error| CacheError::FailedToWrite {
+            error,
+            path: self.dir.display().to_string(),
+        })?;
+
+        let raw_entry = serde_json::to_string(&CacheEntry {
+            key: key.clone(),
+            fetched_at: Utc::now(),
+            data,
+        })
+        .map_err(|error| CacheError::FailedToStringify { error })?;
+
+        let path = self.path(key);
+        write(&path, raw_entry).map_err(|error| CacheError::FailedToWrite {
+            error,
+            path: path.display().to_string(),
+        })?;
+
+        Ok(())
+    }
+
+    /// Remove all entries, returns their number
+    pub fn clear(&self) -> Result<usize> {
+        let mut removed = 0;
+
+        for path in self.entry_paths()? {
+            remove_file(&path).map_err(|error| CacheError::FailedToRemove {
+                error,
+                path: path.display().to_string(),
+            })?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Count entries and their size
+    pub fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats {
+            dir: self.dir.clone(),
+            entries: 0,
+            expired: 0,
+            size: 0,
+        };
+
+        for path in self.entry_paths()? {
+            let Ok(raw_entry) = read_to_string(&path) else {
+                continue;
+            };
+
+            stats.entries += 1;
+            stats.size += raw_entry.len() as u64;
+
+            // Data is not needed to check the age of entry
+            let fresh = serde_json::from_str::<CacheEntry<serde::de::IgnoredAny>>(&raw_entry)
+                .map_or(false, |entry| entry.is_fresh());
+            if !fresh {
+                stats.expired += 1;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Paths of all entry files, missing directory => no entries
+    fn entry_paths(&self) -> Result<Vec<PathBuf>> {
+        Ok(
+            json_files(&self.dir).map_err(|error| CacheError::FailedToRead {
+                error,
+                path: self.dir.display().to_string(),
+            })?,
+        )
+    }
+
+    /// File of the entry, named by hash of the key
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        let hash = fnv1a(serde_json::to_string(key).unwrap_or_default().as_bytes());
+
+        self.dir.join(format!("{hash:016x}.json"))
+    }
+}
+
+/// Provider wrapper that serves responses from the cache and keeps fetched forecasts in the store,
+/// gives forecasts already converted to the unit system
+pub struct CachedProvider<'a> {
+    provider: &'a Provider,
+    units: Units,
+    mode: CacheMode,
+    cache: Option<Cache>,
+    store: Option<ForecastStore>,
+    /// Origin of the last forecasts if they are taken from the store
+    origin: Cell<Option<Origin>>,
+    /// Agreement of ensemble members on the last forecasts
+    agreement: RefCell<Vec<Agreement>>,
+}
+
+/// Forecasts of the response with agreement of members on them if the provider is Ensemble
+#[derive(Serialize, Deserialize)]
+struct Combined {
+    forecasts: Vec<Forecast>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    agreement: Vec<Agreement>,
+}
+
+impl<'a> CachedProvider<'a> {
+    pub fn new(provider: &'a Provider, units: Units, mode: CacheMode) -> Self {
+        // Commands still work without the cache, e.g. if there is no home directory
+        let (cache, store) = match mode {
+            CacheMode::Disabled => (None, None),
+            _ => (Cache::new().ok(), ForecastStore::new().ok()),
+        };
+
+        CachedProvider {
+            provider,
+            units,
+            mode,
+            cache,
+            store,
+            origin: Cell::new(None),
+            agreement: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Type of the wrapped provider
+    pub fn provider_type(&self) -> ProviderType {
+        self.provider.provider_type()
+    }
+
+    /// Origin of the last forecasts, none => they are fetched now or fresh cached ones
+    pub fn origin(&self) -> Option<Origin> {
+        self.origin.get()
+    }
+
+    /// Agreement of ensemble members on the last forecasts, it is given only once,
+    /// empty for other providers and stored forecasts
+    pub fn take_agreement(&self) -> Vec<Agreement> {
+        self.agreement.take()
+    }
+
+    pub fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        let endpoint = match date {
+            Some(_) => Endpoint::Daily,
+            None => Endpoint::Current,
+        };
+
+        let mut forecasts = self.forecasts(
+            endpoint,
+            location,
+            (date.copied(), date.copied()),
+            offset,
+            || Ok(vec![self.provider.get_weather(location, date, offset)?]),
+        )?;
+
+        Ok(forecasts.remove(0))
+    }
+
+    pub fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        self.forecasts(
+            Endpoint::Range,
+            location,
+            (Some(*from), Some(*to)),
+            offset,
+            || self.provider.get_forecast_range(location, from, to, offset),
+        )
+    }
+
+    pub fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        self.forecasts(
+            Endpoint::Hourly,
+            location,
+            (Some(*date), Some(*date)),
+            offset,
+            || self.provider.get_hourly(location, date, offset),
+        )
+    }
+
+    pub fn get_history(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        let mut forecasts = self.forecasts(
+            Endpoint::History,
+            location,
+            (Some(*date), Some(*date)),
+            offset,
+            || Ok(vec![self.provider.get_history(location, date, offset)?]),
+        )?;
+
+        Ok(forecasts.remove(0))
+    }
+
+    pub fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        let key = CacheKey {
+            provider: self.provider.provider_type(),
+            base_url: self.provider.base_url(),
+            endpoint: Endpoint::Timezone,
+            location: location.clone(),
+            from: None,
+            to: None,
+            units: None,
+            offset: None,
+        };
+
+        // Offset is cached in seconds
+        let fetch = || Ok(self.provider.get_utc_offset(location)?.local_minus_utc());
+        let seconds = match self.cached(&key, fetch) {
+            // Outdated offset is still better than the one of the machine
+            Err(error) if self.mode == CacheMode::Offline || is_network_error(&error) => self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get::<i32>(&key))
+                .map(|entry| entry.data)
+                .ok_or(error)?,
+            result => result?,
+        };
+
+        // Broken entry is treated as unknown timezone
+        Ok(FixedOffset::east_opt(seconds).ok_or(ProviderError::TimezoneNotReported)?)
+    }
+
+    /// Alerts are not kept in the store, outdated ones would be misleading
+    pub fn get_alerts(&self, location: &Location) -> Result<Vec<Alert>> {
+        let key = CacheKey {
+            provider: self.provider.provider_type(),
+            base_url: self.provider.base_url(),
+            endpoint: Endpoint::Alerts,
+            location: location.clone(),
+            from: None,
+            to: None,
+            units: None,
+            offset: None,
+        };
+
+        self.cached(&key, || self.provider.get_alerts(location))
+    }
+
+    /// Air quality changes quickly, so it is not kept in the store
+    pub fn get_air_quality(&self, location: &Location) -> Result<AirQuality> {
+        let key = CacheKey {
+            provider: self.provider.provider_type(),
+            base_url: self.provider.base_url(),
+            endpoint: Endpoint::AirQuality,
+            location: location.clone(),
+            from: None,
+            to: None,
+            units: None,
+            offset: None,
+        };
+
+        self.cached(&key, || self.provider.get_air_quality(location))
+    }
+
+    /// Astronomy data of the date does not change, so it is served regardless of its age
+    /// if the provider is not reachable
+    pub fn get_astronomy(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Astronomy> {
+        let key = CacheKey {
+            provider: self.provider.provider_type(),
+            base_url: self.provider.base_url(),
+            endpoint: Endpoint::Astronomy,
+            location: location.clone(),
+            from: Some(*date),
+            to: Some(*date),
+            units: None,
+            offset: Some(offset.local_minus_utc()),
+        };
+
+        match self.cached(&key, || self.provider.get_astronomy(location, date, offset)) {
+            Err(error) if is_network_error(&error) => Ok(self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get::<Astronomy>(&key))
+                .map(|entry| entry.data)
+                .ok_or(error)?),
+            result => result,
+        }
+    }
+
+    /// Forecasts for the dates [from, to] (none => current weather) from the cache,
+    /// fetched ones or stored ones if the provider is not reachable, converted to the unit system
+    fn forecasts(
+        &self,
+        endpoint: Endpoint,
+        location: &Location,
+        (from, to): (Option<NaiveDate>, Option<NaiveDate>),
+        offset: FixedOffset,
+        fetch: impl FnOnce() -> Result<Vec<Forecast>>,
+    ) -> Result<Vec<Forecast>> {
+        let key = CacheKey {
+            provider: self.provider.provider_type(),
+            base_url: self.provider.base_url(),
+            endpoint,
+            location: location.clone(),
+            from,
+            to,
+            units: Some(self.units),
+            offset: Some(offset.local_minus_utc()),
+        };
+        let today = date::today(offset);
+
+        let fetched = match self.mode {
+            CacheMode::Offline => Err(StoreError::ForecastNotStored {
+                location: location.to_string(),
+                date: match (from, to) {
+                    (Some(from), Some(to)) if from != to => {
+                        format!("{} - {}", from.format("%d.%m.%Y"), to.format("%d.%m.%Y"))
+                    }
+                    (Some(date), _) => date.format("%d.%m.%Y").to_string(),
+                    (None, _) => today.format("%d.%m.%Y").to_string(),
+                },
+            }
+            .into()),
+            _ => self.cached(&key, || {
+                let forecasts = fetch()?;
+                self.store(endpoint, location, from.unwrap_or(today), &forecasts);
+
+                let agreement = match self.provider {
+                    Provider::Ensemble(ensemble) => ensemble.take_agreement(),
+                    _ => Vec::new(),
+                };
+
+                Ok(Combined {
+                    forecasts: self.convert(forecasts),
+                    agreement: agreement
+                        .into_iter()
+                        .map(|agreement| agreement.into_units(self.units))
+                        .collect(),
+                })
+            }),
+        };
+
+        let forecasts = match fetched {
+            Ok(combined) => {
+                self.agreement.replace(
+                    combined
+                        .agreement
+                        .into_iter()
+                        .map(|agreement| Agreement {
+                            units: self.units,
+                            ..agreement
+                        })
+                        .collect(),
+                );
+                combined.forecasts
+            }
+            Err(error) if self.mode == CacheMode::Offline || is_network_error(&error) => {
+                let Some(stored) = self.restore(endpoint, location, (from, to), today) else {
+                    return Err(error);
+                };
+
+                self.origin.set(Some(stored.origin));
+                self.convert(stored.forecasts)
+            }
+            Err(error) => return Err(error),
+        };
+
+        // Unit system is not stored with each forecast
+        Ok(forecasts
+            .into_iter()
+            .map(|forecast| Forecast {
+                units: self.units,
+                ..forecast
+            })
+            .collect())
+    }
+
+    /// Convert metric forecasts to the unit system
+    fn convert(&self, forecasts: Vec<Forecast>) -> Vec<Forecast> {
+        forecasts
+            .into_iter()
+            .map(|forecast| forecast.into_units(self.units))
+            .collect()
+    }
+
+    /// Keep fetched metric forecasts in the store by their dates, current weather is kept for today
+    fn store(
+        &self,
+        endpoint: Endpoint,
+        location: &Location,
+        date: NaiveDate,
+        forecasts: &[Forecast],
+    ) {
+        let Some(store) = &self.store else {
+            return;
+        };
+
+        let origin = Origin {
+            provider: self.provider.provider_type(),
+            fetched_at: Utc::now(),
+        };
+        let days = match endpoint {
+            Endpoint::Current => vec![(date, StoredKind::Current, forecasts.to_vec())],
+            // Past weather takes place of the forecast for its date
+            Endpoint::Daily | Endpoint::History => {
+                vec![(date, StoredKind::Daily, forecasts.to_vec())]
+            }
+            Endpoint::Hourly => vec![(date, StoredKind::Hourly, forecasts.to_vec())],
+            // Each day of the range can be requested separately later
+            Endpoint::Range => forecasts
+                .iter()
+                .map(|forecast| {
+                    (
+                        forecast.date.unwrap_or(date),
+                        StoredKind::Daily,
+                        vec![forecast.clone()],
+                    )
+                })
+                .collect(),
+            Endpoint::Timezone
+            | Endpoint::Alerts
+            | Endpoint::AirQuality
+            | Endpoint::Astronomy
+            | Endpoint::Geocode => Vec::new(),
+        };
+
+        for (date, kind, forecasts) in days {
+            // Response is already received, so failed storing only warns
+            if let Err(error) = store.save(location, &date, kind, Stored { origin, forecasts }) {
+                eprintln!("Warning: {error}");
+            }
+        }
+
+        // Forecasts that are not updated for long are of no use even offline
+        if let Err(error) = store.prune() {
+            eprintln!("Warning: {error}");
+        }
+    }
+
+    /// Most recent stored forecasts for the request, range needs each of its days
+    fn restore(
+        &self,
+        endpoint: Endpoint,
+        location: &Location,
+        (from, to): (Option<NaiveDate>, Option<NaiveDate>),
+        today: NaiveDate,
+    ) -> Option<Stored> {
+        let store = self.store.as_ref()?;
+
+        match endpoint {
+            // Forecast for today is better than nothing
+            Endpoint::Current => store
+                .load(location, &today, StoredKind::Current)
+                .or_else(|| store.load(location, &today, StoredKind::Daily)),
+            Endpoint::Daily | Endpoint::History => store.load(location, &from?, StoredKind::Daily),
+            Endpoint::Hourly => store.load(location, &from?, StoredKind::Hourly),
+            Endpoint::Range => {
+                let to = to?;
+                let mut days = from?
+                    .iter_days()
+                    .take_while(|date| *date <= to)
+                    .map(|date| store.load(location, &date, StoredKind::Daily))
+                    .collect::<Option<Vec<_>>>()?;
+
+                // The oldest day determines age of the whole range
+                days.sort_by_key(|day| day.origin.fetched_at);
+                let origin = days.first()?.origin;
+
+                let mut forecasts = days
+                    .into_iter()
+                    .flat_map(|day| day.forecasts)
+                    .collect::<Vec<_>>();
+                forecasts.sort_by_key(|forecast| forecast.date);
+
+                Some(Stored { origin, forecasts })
+            }
+            Endpoint::Timezone
+            | Endpoint::Alerts
+            | Endpoint::AirQuality
+            | Endpoint::Astronomy
+            | Endpoint::Geocode => None,
+        }
+    }
+
+    /// Fresh data from the cache or fetched one, which is stored then
+    fn cached<T: Serialize + DeserializeOwned>(
+        &self,
+        key: &CacheKey,
+        fetch: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let Some(cache) = &self.cache else {
+            return fetch();
+        };
+
+        match self.mode {
+            CacheMode::Enabled => {
+                if let Some(entry) = cache.get::<T>(key).filter(CacheEntry::is_fresh) {
+                    return Ok(entry.data);
+                }
+            }
+            CacheMode::Offline => {
+                return Ok(cache
+                    .get::<T>(key)
+                    .ok_or(CacheError::ResponseNotCached)?
+                    .data)
+            }
+            CacheMode::Refresh | CacheMode::Disabled => {}
+        }
+
+        let data = fetch()?;
+
+        // Response is already received, so failed caching only warns
+        if let Err(error) = cache.put(key, &data) {
+            eprintln!("Warning: {error}");
+        }
+
+        Ok(data)
+    }
+}
+
+impl Geocoder for CachedProvider<'_> {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        let key = CacheKey {
+            provider: self.provider.provider_type(),
+            base_url: self.provider.base_url(),
+            endpoint: Endpoint::Geocode,
+            location: Location::Address(name.to_owned()),
+            from: None,
+            to: None,
+            units: None,
+            offset: None,
+        };
+
+        match self.cached(&key, || self.provider.geocode(name)) {
+            // Places found before are still valid, unknown address is passed as is
+            Err(error) if self.mode == CacheMode::Offline || is_network_error(&error) => Ok(self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get::<Vec<Place>>(&key))
+                .map(|entry| entry.data)
+                .unwrap_or_default()),
+            result => result,
+        }
+    }
+}
+
+/// Paths of JSON files in the directory, missing directory => no files
+pub fn json_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    Ok(entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "json")
+        })
+        .collect())
+}
+
+/// 64-bit FNV-1a hash, unlike the hasher of std it is the same for every build,
+/// so entries stay in their files after updates of the toolchain
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Check that the request failed because the provider is not reachable
+fn is_network_error(error: &eyre::Report) -> bool {
+    error.chain().any(|cause| cause.is::<reqwest::Error>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(base_url: &str) -> CacheKey {
+        CacheKey {
+            provider: ProviderType::OpenWeather,
+            base_url: base_url.to_owned(),
+            endpoint: Endpoint::Current,
+            location: Location::Coordinates {
+                lat: 35.69,
+                lon: 139.69,
+            },
+            from: None,
+            to: None,
+            units: Some(Units::Metric),
+            offset: None,
+        }
+    }
+
+    #[test]
+    fn hash_is_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn entry_file_is_stable() {
+        let cache = Cache {
+            dir: PathBuf::from("cache"),
+        };
+
+        // The name must not change between builds, or cached entries are lost
+        assert_eq!(
+            cache.path(&key("https://api.openweathermap.org")),
+            PathBuf::from("cache/411913fd38a342eb.json")
+        );
+        assert_ne!(
+            cache.path(&key("https://api.openweathermap.org")),
+            cache.path(&key("http://localhost:8080"))
+        );
+
+        // Forecasts are bucketed into dates by the offset, so each one has its own entry
+        let tokyo = CacheKey {
+            offset: Some(9 * 3600),
+            ..key("https://api.openweathermap.org")
+        };
+        assert_ne!(
+            cache.path(&tokyo),
+            cache.path(&key("https://api.openweathermap.org"))
+        );
+    }
+}
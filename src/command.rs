@@ -1,12 +1,32 @@
-use clap::{Parser, Subcommand};
+use chrono::FixedOffset;
+use clap::{ArgGroup, Args as ClapArgs, Parser, Subcommand};
 use eyre::Result;
+use std::process::ExitCode;
 
-use self::configure::{set_provider, CONFIG_PATH};
-use self::get::get;
+use self::air::air;
+use self::alerts::alerts;
+use self::astro::astro;
+use self::cache::{cache_stats, clear_cache};
+use self::configure::{set_priority, set_provider, set_units, set_weights, CONFIG_PATH};
+use self::geocode::geocode;
+use self::get::{get, get_all, get_hourly, get_range, GetOptions};
+use self::location::{add_location, list_locations, remove_location, saved_location};
+use crate::cache::CacheMode;
+use crate::date::{parse_utc_offset, TIMEZONE_FORMS};
+use crate::location::Location;
+use crate::output::OutputFormat;
+use crate::provider::{ProviderType, PROVIDER_TYPE_MAP};
 use crate::provider_loader::json_loader::JsonLoader;
+use crate::units::Units;
 
+mod air;
+mod alerts;
+mod astro;
+mod cache;
 mod configure;
+mod geocode;
 mod get;
+mod location;
 
 /// CLI for getting information about weather
 #[derive(Parser)]
@@ -18,41 +38,565 @@ pub struct Args {
 
 #[derive(Subcommand)]
 pub enum Command {
-    /// Configure the provider and API key for it
+    /// Configure the provider and API key for it, the unit system, the provider priority
+    /// or weights of providers in Ensemble
     Configure {
-        /// Provider name (WeatherApi, OpenWeather)
-        #[clap(short, long)]
-        provider: String,
+        /// Provider name (WeatherApi, OpenWeather, OpenMeteo, TomorrowIo, VisualCrossing, Nws, MetNorway, Ensemble)
+        #[clap(short, long, required_unless_present_any = ["units", "priority", "weights"])]
+        provider: Option<String>,
 
         /// API key for the provider, during the first configuration is necessary
-        #[clap(short, long)]
+        #[clap(short, long, requires = "provider")]
         api_key: Option<String>,
+
+        /// Contact (e.g. email) sent in User-Agent header, during the first configuration
+        /// of Nws and MetNorway is necessary
+        #[clap(long, requires = "provider")]
+        contact: Option<String>,
+
+        /// Base URL of the provider API (empty string resets it to the default one)
+        #[clap(short, long, requires = "provider")]
+        base_url: Option<String>,
+
+        /// Default unit system
+        #[clap(short, long, value_enum)]
+        units: Option<Units>,
+
+        /// Providers to try in order if the default one fails (empty string disables fallback)
+        #[clap(long, value_delimiter = ',')]
+        priority: Option<Vec<String>>,
+
+        /// Weights of providers in Ensemble as <provider>=<number> (e.g. WeatherApi=2,OpenWeather=1)
+        #[clap(long, value_delimiter = ',')]
+        weights: Option<Vec<String>>,
     },
 
-    /// Get weather information for the address on the date
+    /// Get weather information for the location on the date
     Get {
-        /// Name of city
+        #[command(flatten)]
+        location: LocationArgs,
+
+        /// Name of saved location [default: the default saved location]
+        #[clap(short = 'l', long = "location", conflicts_with = "location")]
+        saved_location: Option<String>,
+
+        /// Date (e.g. 20.10.2026, 2026-10-20, tomorrow, +3d, friday, next friday) [default: now]
+        #[clap(short, long, allow_hyphen_values = true, conflicts_with_all = ["from", "to", "days"])]
+        date: Option<String>,
+
+        /// First date of the range forecast in any form of the date [default: today]
+        #[clap(long, allow_hyphen_values = true)]
+        from: Option<String>,
+
+        /// Last date of the range forecast in any form of the date [default: the first one]
+        #[clap(long, allow_hyphen_values = true, conflicts_with = "days")]
+        to: Option<String>,
+
+        /// Number of days in the range forecast
+        #[clap(long)]
+        days: Option<u32>,
+
+        /// Show hourly forecast for the date
+        #[clap(long, conflicts_with_all = ["from", "to", "days"])]
+        hourly: bool,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t)]
+        output: OutputFormat,
+
+        /// Unit system [default: from config]
+        #[clap(short, long, value_enum)]
+        units: Option<Units>,
+
+        /// Neither use nor store cached responses
+        #[clap(long)]
+        no_cache: bool,
+
+        /// Ignore cached responses, but store new ones
+        #[clap(long, conflicts_with = "no_cache")]
+        refresh: bool,
+
+        /// Do not send requests, show cached responses and last known forecasts
+        #[clap(long, conflicts_with_all = ["no_cache", "refresh"])]
+        offline: bool,
+
+        /// Compare forecasts of all configured providers
+        #[clap(long, conflicts_with_all = ["from", "to", "days", "hourly", "offline"])]
+        all_providers: bool,
+
+        /// Provider to use for this call only [default: from config]
+        #[clap(short, long, value_parser = parse_provider, conflicts_with = "all_providers")]
+        provider: Option<ProviderType>,
+
+        /// Show current air quality with current weather
+        #[clap(long, conflicts_with_all = ["date", "from", "to", "days", "hourly", "all_providers"])]
+        air_quality: bool,
+
+        /// UTC offset of the timezone to count dates in (e.g. UTC, +09:00, -0530)
+        /// [default: timezone of the location, or the local one if the provider does not report it]
+        #[clap(long, value_parser = parse_timezone, allow_hyphen_values = true)]
+        tz: Option<FixedOffset>,
+    },
+
+    /// Get current air quality at the location
+    Air {
+        #[command(flatten)]
+        location: LocationArgs,
+
+        /// Name of saved location [default: the default saved location]
+        #[clap(short = 'l', long = "location", conflicts_with = "location")]
+        saved_location: Option<String>,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t)]
+        output: OutputFormat,
+
+        /// Neither use nor store cached responses
+        #[clap(long)]
+        no_cache: bool,
+
+        /// Ignore cached responses, but store new ones
+        #[clap(long, conflicts_with = "no_cache")]
+        refresh: bool,
+
+        /// Provider to use for this call only [default: from config]
+        #[clap(short, long, value_parser = parse_provider)]
+        provider: Option<ProviderType>,
+    },
+
+    /// Get sunrise, sunset and moon phase at the location on the date
+    Astro {
+        #[command(flatten)]
+        location: LocationArgs,
+
+        /// Name of saved location [default: the default saved location]
+        #[clap(short = 'l', long = "location", conflicts_with = "location")]
+        saved_location: Option<String>,
+
+        /// Date in any form of the date of get command [default: today]
+        #[clap(short, long, allow_hyphen_values = true)]
+        date: Option<String>,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t)]
+        output: OutputFormat,
+
+        /// Neither use nor store cached responses
+        #[clap(long)]
+        no_cache: bool,
+
+        /// Ignore cached responses, but store new ones
+        #[clap(long, conflicts_with = "no_cache")]
+        refresh: bool,
+
+        /// Do not send requests, show cached responses or calculate the data
+        #[clap(long, conflicts_with_all = ["no_cache", "refresh"])]
+        offline: bool,
+
+        /// Provider to use for this call only [default: from config]
+        #[clap(short, long, value_parser = parse_provider)]
+        provider: Option<ProviderType>,
+
+        /// UTC offset of the timezone to show times in (e.g. UTC, +09:00, -0530)
+        /// [default: timezone of the location, or the local one if the provider does not report it]
+        #[clap(long, value_parser = parse_timezone, allow_hyphen_values = true)]
+        tz: Option<FixedOffset>,
+    },
+
+    /// Get active severe weather alerts for the location,
+    /// exits with code 3 if there are any of them
+    Alerts {
+        #[command(flatten)]
+        location: LocationArgs,
+
+        /// Name of saved location [default: the default saved location]
+        #[clap(short = 'l', long = "location", conflicts_with = "location")]
+        saved_location: Option<String>,
+
+        /// Output format
+        #[clap(short, long, value_enum, default_value_t)]
+        output: OutputFormat,
+
+        /// Neither use nor store cached responses
+        #[clap(long)]
+        no_cache: bool,
+
+        /// Ignore cached responses, but store new ones
+        #[clap(long, conflicts_with = "no_cache")]
+        refresh: bool,
+
+        /// Provider to use for this call only [default: from config]
+        #[clap(short, long, value_parser = parse_provider)]
+        provider: Option<ProviderType>,
+    },
+
+    /// Find places matching the name to resolve ambiguous addresses
+    Geocode {
+        /// Name of place
         #[clap(short, long)]
         address: String,
+    },
+
+    /// Manage named locations
+    Location {
+        #[clap(subcommand)]
+        action: LocationAction,
+    },
+
+    /// Manage cached provider responses
+    Cache {
+        #[clap(subcommand)]
+        action: CacheAction,
+    },
+}
 
-        /// Date [default: now]
+#[derive(Subcommand)]
+pub enum LocationAction {
+    /// Save the location under the name
+    Add {
+        /// Name of the location
         #[clap(short, long)]
-        date: Option<String>,
+        name: String,
+
+        #[command(flatten)]
+        location: LocationArgs,
+
+        /// Make the location the default one
+        #[clap(long)]
+        default: bool,
+    },
+
+    /// Remove the saved location
+    Remove {
+        /// Name of the location
+        #[clap(short, long)]
+        name: String,
     },
+
+    /// List saved locations
+    List,
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Remove all cached responses and stored forecasts
+    Clear,
+
+    /// Show number and size of cached responses
+    Stats,
+}
+
+/// Arguments that set the location
+#[derive(ClapArgs)]
+#[clap(group(ArgGroup::new("location").args(["address", "lat", "zip", "city_id"])))]
+pub struct LocationArgs {
+    /// Name of city
+    #[clap(short, long)]
+    address: Option<String>,
+
+    /// Latitude in degrees
+    #[clap(long, requires = "lon", allow_negative_numbers = true)]
+    lat: Option<f64>,
+
+    /// Longitude in degrees
+    #[clap(long, requires = "lat", allow_negative_numbers = true)]
+    lon: Option<f64>,
+
+    /// Postal code in format <code>,<country> (country is ISO 3166 code)
+    #[clap(short, long)]
+    zip: Option<String>,
+
+    /// City ID of the provider
+    #[clap(long)]
+    city_id: Option<u64>,
+}
+
+impl LocationArgs {
+    /// Build location from the one set argument
+    fn to_location(&self) -> Result<Option<Location>> {
+        Ok(Some(match self {
+            LocationArgs {
+                lat: Some(lat),
+                lon: Some(lon),
+                ..
+            } => Location::Coordinates {
+                lat: *lat,
+                lon: *lon,
+            },
+            LocationArgs { zip: Some(zip), .. } => Location::parse_zip(zip)?,
+            LocationArgs {
+                city_id: Some(id), ..
+            } => Location::CityId(*id),
+            LocationArgs {
+                address: Some(address),
+                ..
+            } => Location::Address(address.clone()),
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Location from arguments, or the saved one if none is set
+    fn to_location_or_saved(&self, name: Option<&str>) -> Result<Location> {
+        match self.to_location()? {
+            Some(location) => Ok(location),
+            None => saved_location::<JsonLoader>(name, CONFIG_PATH),
+        }
+    }
 }
 
+/// Exit code of alerts command if there are active alerts,
+/// it differs from the one of failure (1) and of invalid arguments (2)
+const ACTIVE_ALERTS_EXIT_CODE: u8 = 3;
+
 /// Processing action for each command
 impl Command {
-    pub fn process(&self) -> Result<()> {
+    pub fn process(&self) -> Result<ExitCode> {
         match self {
-            Command::Configure { provider, api_key } => {
-                set_provider::<JsonLoader>(provider, api_key.as_deref(), CONFIG_PATH)?
+            Command::Configure {
+                provider,
+                api_key,
+                contact,
+                base_url,
+                units,
+                priority,
+                weights,
+            } => {
+                if let Some(provider) = provider {
+                    set_provider::<JsonLoader>(
+                        provider,
+                        api_key.as_deref(),
+                        contact.as_deref(),
+                        base_url.as_deref(),
+                        CONFIG_PATH,
+                    )?
+                }
+
+                if let Some(units) = units {
+                    set_units::<JsonLoader>(*units, CONFIG_PATH)?
+                }
+
+                if let Some(priority) = priority {
+                    set_priority::<JsonLoader>(priority, CONFIG_PATH)?
+                }
+
+                if let Some(weights) = weights {
+                    set_weights::<JsonLoader>(weights, CONFIG_PATH)?
+                }
             }
-            Command::Get { address, date } => {
-                get::<JsonLoader>(address, date.as_deref(), CONFIG_PATH)?
+            Command::Get {
+                location,
+                saved_location,
+                date,
+                all_providers: true,
+                output,
+                units,
+                no_cache,
+                refresh,
+                tz,
+                ..
+            } => get_all::<JsonLoader>(
+                &location.to_location_or_saved(saved_location.as_deref())?,
+                date.as_deref(),
+                GetOptions {
+                    output: *output,
+                    units: *units,
+                    cache: cache_mode(*no_cache, *refresh, false),
+                    provider: None,
+                    tz: *tz,
+                },
+                CONFIG_PATH,
+            )?,
+            Command::Get {
+                location,
+                saved_location,
+                from,
+                to,
+                days,
+                output,
+                units,
+                no_cache,
+                refresh,
+                offline,
+                provider,
+                tz,
+                ..
+            } if from.is_some() || to.is_some() || days.is_some() => get_range::<JsonLoader>(
+                &location.to_location_or_saved(saved_location.as_deref())?,
+                from.as_deref(),
+                to.as_deref(),
+                *days,
+                GetOptions {
+                    output: *output,
+                    units: *units,
+                    cache: cache_mode(*no_cache, *refresh, *offline),
+                    provider: *provider,
+                    tz: *tz,
+                },
+                CONFIG_PATH,
+            )?,
+            Command::Get {
+                location,
+                saved_location,
+                date,
+                hourly: true,
+                output,
+                units,
+                no_cache,
+                refresh,
+                offline,
+                provider,
+                tz,
+                ..
+            } => get_hourly::<JsonLoader>(
+                &location.to_location_or_saved(saved_location.as_deref())?,
+                date.as_deref(),
+                GetOptions {
+                    output: *output,
+                    units: *units,
+                    cache: cache_mode(*no_cache, *refresh, *offline),
+                    provider: *provider,
+                    tz: *tz,
+                },
+                CONFIG_PATH,
+            )?,
+            Command::Get {
+                location,
+                saved_location,
+                date,
+                output,
+                units,
+                no_cache,
+                refresh,
+                offline,
+                provider,
+                air_quality,
+                tz,
+                ..
+            } => get::<JsonLoader>(
+                &location.to_location_or_saved(saved_location.as_deref())?,
+                date.as_deref(),
+                *air_quality,
+                GetOptions {
+                    output: *output,
+                    units: *units,
+                    cache: cache_mode(*no_cache, *refresh, *offline),
+                    provider: *provider,
+                    tz: *tz,
+                },
+                CONFIG_PATH,
+            )?,
+            Command::Air {
+                location,
+                saved_location,
+                output,
+                no_cache,
+                refresh,
+                provider,
+            } => air::<JsonLoader>(
+                &location.to_location_or_saved(saved_location.as_deref())?,
+                GetOptions {
+                    output: *output,
+                    units: None,
+                    cache: cache_mode(*no_cache, *refresh, false),
+                    provider: *provider,
+                    tz: None,
+                },
+                CONFIG_PATH,
+            )?,
+            Command::Astro {
+                location,
+                saved_location,
+                date,
+                output,
+                no_cache,
+                refresh,
+                offline,
+                provider,
+                tz,
+            } => astro::<JsonLoader>(
+                &location.to_location_or_saved(saved_location.as_deref())?,
+                date.as_deref(),
+                GetOptions {
+                    output: *output,
+                    units: None,
+                    cache: cache_mode(*no_cache, *refresh, *offline),
+                    provider: *provider,
+                    tz: *tz,
+                },
+                CONFIG_PATH,
+            )?,
+            Command::Alerts {
+                location,
+                saved_location,
+                output,
+                no_cache,
+                refresh,
+                provider,
+            } => {
+                let active = alerts::<JsonLoader>(
+                    &location.to_location_or_saved(saved_location.as_deref())?,
+                    GetOptions {
+                        output: *output,
+                        units: None,
+                        cache: cache_mode(*no_cache, *refresh, false),
+                        provider: *provider,
+                        tz: None,
+                    },
+                    CONFIG_PATH,
+                )?;
+
+                if active > 0 {
+                    return Ok(ExitCode::from(ACTIVE_ALERTS_EXIT_CODE));
+                }
             }
+            Command::Geocode { address } => geocode::<JsonLoader>(address, CONFIG_PATH)?,
+            Command::Location { action } => match action {
+                LocationAction::Add {
+                    name,
+                    location,
+                    default,
+                } => add_location::<JsonLoader>(
+                    name,
+                    location.to_location()?,
+                    *default,
+                    CONFIG_PATH,
+                )?,
+                LocationAction::Remove { name } => {
+                    remove_location::<JsonLoader>(name, CONFIG_PATH)?
+                }
+                LocationAction::List => list_locations::<JsonLoader>(CONFIG_PATH)?,
+            },
+            Command::Cache { action } => match action {
+                CacheAction::Clear => clear_cache()?,
+                CacheAction::Stats => cache_stats()?,
+            },
         }
 
-        Ok(())
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Parse name of registered provider
+fn parse_provider(name: &str) -> Result<ProviderType, String> {
+    PROVIDER_TYPE_MAP
+        .get(name)
+        .copied()
+        .ok_or(format!("Provider {name} is not supported"))
+}
+
+/// Parse UTC offset of timezone
+fn parse_timezone(text: &str) -> Result<FixedOffset, String> {
+    parse_utc_offset(text).ok_or(format!(
+        "Timezone {text} is in unsupported format, use one of: {TIMEZONE_FORMS}"
+    ))
+}
+
+/// Cache mode set by flags of get command
+fn cache_mode(no_cache: bool, refresh: bool, offline: bool) -> CacheMode {
+    match (no_cache, refresh, offline) {
+        (true, _, _) => CacheMode::Disabled,
+        (_, true, _) => CacheMode::Refresh,
+        (_, _, true) => CacheMode::Offline,
+        _ => CacheMode::Enabled,
     }
 }
@@ -0,0 +1,29 @@
+use eyre::Result;
+
+use super::get::{resolve_once, with_fallback, GetOptions};
+use crate::{location::Location, output::AirQualityReport, provider_loader::ProviderLoader};
+
+/// Print current air quality at the location
+pub fn air<Loader: ProviderLoader>(
+    location: &Location,
+    options: GetOptions,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let units = options.units.unwrap_or(loader.get_config()?.units);
+
+    let resolved = resolve_once(&loader, options, units, location)?;
+
+    let (air_quality, provider) = with_fallback(&loader, options, units, |cached| {
+        cached.get_air_quality(&resolved)
+    })?;
+
+    AirQualityReport::new(&location.to_string(), provider, &air_quality).print(
+        options.output,
+        format_args!("Air quality at {location} ({provider:?}):\n{air_quality}"),
+    )?;
+
+    Ok(())
+}
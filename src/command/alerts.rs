@@ -0,0 +1,48 @@
+use chrono::Utc;
+use eyre::Result;
+use std::cmp::Reverse;
+
+use super::get::{resolve_once, with_fallback, GetOptions};
+use crate::{location::Location, output::AlertReport, provider_loader::ProviderLoader};
+
+/// Print active severe weather alerts for the location, gives their number
+pub fn alerts<Loader: ProviderLoader>(
+    location: &Location,
+    options: GetOptions,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<usize> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let units = options.units.unwrap_or(loader.get_config()?.units);
+
+    let resolved = resolve_once(&loader, options, units, location)?;
+
+    let (alerts, provider) = with_fallback(&loader, options, units, |cached| {
+        cached.get_alerts(&resolved)
+    })?;
+
+    // Providers may keep alerts for a while after they are over
+    let now = Utc::now();
+    let mut alerts = alerts
+        .into_iter()
+        .filter(|alert| alert.is_active(now))
+        .collect::<Vec<_>>();
+    alerts.sort_by_key(|alert| Reverse(alert.severity));
+
+    let text = match alerts.is_empty() {
+        true => format!("No active weather alerts for {location} ({provider:?})"),
+        false => format!(
+            "Active weather alerts for {location} ({provider:?}):\n\n{}",
+            alerts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    };
+
+    AlertReport::new(&location.to_string(), provider, &alerts).print(options.output, text)?;
+
+    Ok(alerts.len())
+}
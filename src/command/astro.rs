@@ -0,0 +1,97 @@
+use eyre::Result;
+use thiserror::Error;
+
+use super::get::{estimate_timezone, parse_date, resolve_once, with_fallback, GetOptions};
+use crate::{
+    astronomy::Astronomy,
+    cache::{CacheMode, CachedProvider},
+    date,
+    location::Location,
+    output::AstronomyReport,
+    provider_loader::{LoaderError, ProviderLoader},
+};
+
+/// Errors for action astro
+#[derive(Error, Debug)]
+pub enum AstroError {
+    #[error("Timezone of location {location} is unknown without provider, set it with --tz or use coordinates")]
+    TimezoneUnknown { location: String },
+}
+
+/// Print sunrise, sunset and moon phase at the location on the date (none => today),
+/// the data that providers do not give is calculated if the location is set by coordinates,
+/// without the default provider only the calculated data is printed
+pub fn astro<Loader: ProviderLoader>(
+    location: &Location,
+    date: Option<&str>,
+    options: GetOptions,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let units = options.units.unwrap_or(loader.get_config()?.units);
+
+    // Address and timezone are resolved once, the calculation needs them even if all providers fail
+    let provider = match (options.provider, loader.get_config()?.default) {
+        (Some(provider), _) | (None, Some(provider)) => Some(loader.get_provider(provider)?),
+        (None, None) => None,
+    };
+    let resolved = resolve_once(&loader, options, units, location)?;
+    let reported = provider.as_ref().and_then(|provider| {
+        CachedProvider::new(provider, units, options.cache)
+            .get_utc_offset(&resolved)
+            .ok()
+    });
+    let offset = match options.tz.or(reported) {
+        Some(offset) => offset,
+        // Local timezone of the machine has nothing to do with the location
+        None => estimate_timezone(&resolved).ok_or_else(|| AstroError::TimezoneUnknown {
+            location: resolved.to_string(),
+        })?,
+    };
+    let today = date::today(offset);
+
+    let date = match date {
+        Some(date) => parse_date(date, today)?,
+        None => today,
+    };
+    let calculated = Astronomy::calculate_at(&resolved, &date, offset);
+
+    let result = provider.is_some().then(|| {
+        with_fallback(&loader, options, units, |cached| {
+            cached.get_astronomy(&resolved, &date, offset)
+        })
+    });
+
+    let (astronomy, provider) = match (result, calculated) {
+        // Provider may give only the sun (e.g. OpenWeather)
+        (Some(Ok((astronomy, provider))), Some(calculated)) => {
+            (astronomy.or(calculated), Some(provider))
+        }
+        (Some(Ok((astronomy, provider))), None) => (astronomy, Some(provider)),
+        (Some(Err(error)), Some(calculated)) => {
+            if options.cache != CacheMode::Offline {
+                eprintln!("Astronomy data is calculated, providers failed with error '{error}'");
+            }
+
+            (calculated, None)
+        }
+        (None, Some(calculated)) => (calculated, None),
+        (Some(Err(error)), None) => return Err(error),
+        (None, None) => return Err(LoaderError::DefaultProviderNotSet.into()),
+    };
+
+    let source = provider.map_or("calculated".to_owned(), |provider| format!("{provider:?}"));
+
+    AstronomyReport::new(&location.to_string(), provider, date, &astronomy).print(
+        options.output,
+        format_args!(
+            "Astronomy data for {location} on {date} {timezone} ({source}):\n{astronomy}",
+            date = date.format("%d.%m.%Y"),
+            timezone = date::format_utc_offset(offset),
+        ),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use eyre::Result;
+
+use crate::{cache::Cache, store::ForecastStore};
+
+/// Remove all cached responses and stored forecasts
+pub fn clear_cache() -> Result<()> {
+    let removed = Cache::new()?.clear()?;
+    let forgotten = ForecastStore::new()?.clear()?;
+
+    println!(
+        "Success! {removed} cached responses and {forgotten} days of stored forecasts are removed"
+    );
+
+    Ok(())
+}
+
+/// Print summary of the cache
+pub fn cache_stats() -> Result<()> {
+    let stats = Cache::new()?.stats()?;
+
+    println!("Directory: {}", stats.dir.display());
+    println!("Entries: {}", stats.entries);
+    println!("Expired: {}", stats.expired);
+    println!("Size: {:.1} KiB", stats.size as f64 / 1024.0);
+
+    Ok(())
+}
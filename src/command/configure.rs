@@ -1,7 +1,12 @@
 use eyre::Result;
 use thiserror::Error;
 
-use crate::{provider::PROVIDER_TYPE_MAP, provider_loader::ProviderLoader};
+use crate::{
+    config::Config,
+    provider::{ProviderType, PROVIDER_TYPE_MAP},
+    provider_loader::ProviderLoader,
+    units::Units,
+};
 
 /// Path to the config file
 pub const CONFIG_PATH: &str = "config.json";
@@ -13,12 +18,18 @@ pub enum ConfigurationError {
     ProviderNotSupported { provider: String },
     #[error("Provider {provider} does not have API key")]
     NotHasAPIKey { provider: String },
+    #[error("Provider {provider} requires contact (e.g. email) for User-Agent header, set it with --contact")]
+    NotHasContact { provider: String },
+    #[error("Weight {weight} should be in format <provider>=<number> with finite number >= 0")]
+    InvalidWeight { weight: String },
 }
 
-/// Set default provider or API key for provider
+/// Set default provider, API key, contact or base URL for provider
 pub fn set_provider<Loader: ProviderLoader>(
     provider: &str,
     api_key: Option<&str>,
+    contact: Option<&str>,
+    base_url: Option<&str>,
     loader_args: Loader::ConstructorArg<'_>,
 ) -> Result<()> {
     // Check that provider is registered
@@ -37,11 +48,34 @@ pub fn set_provider<Loader: ProviderLoader>(
     if let Some(api_key) = api_key {
         loader.set_provider_key(provider_type, api_key)?
     } else if loader.get_config()?.keys.get(&provider_type).is_none() {
-        return Err(ConfigurationError::NotHasAPIKey {
+        if provider_type.requires_key() {
+            return Err(ConfigurationError::NotHasAPIKey {
+                provider: provider.to_owned(),
+            }
+            .into());
+        }
+
+        // Provider without API key is configured too, so it stays configured after default changes
+        if provider_type.is_real() {
+            loader.set_provider_key(provider_type, "")?
+        }
+    };
+
+    // Provider that identifies the user by contact can not work without it, like without API key
+    if let Some(contact) = contact.map(str::trim).filter(|contact| !contact.is_empty()) {
+        loader.set_provider_contact(provider_type, contact)?
+    } else if provider_type.requires_contact()
+        && loader.get_config()?.contacts.get(&provider_type).is_none()
+    {
+        return Err(ConfigurationError::NotHasContact {
             provider: provider.to_owned(),
         }
         .into());
-    };
+    }
+
+    if let Some(base_url) = base_url {
+        loader.set_provider_base_url(provider_type, base_url)?
+    }
 
     loader.set_default_provider(provider_type)?;
     loader.save_config()?;
@@ -50,3 +84,159 @@ pub fn set_provider<Loader: ProviderLoader>(
 
     Ok(())
 }
+
+/// Set providers to fall back to in order, empty list => no fallback
+pub fn set_priority<Loader: ProviderLoader>(
+    priority: &[String],
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let mut provider_types = Vec::new();
+    for provider in priority.iter().filter(|provider| !provider.is_empty()) {
+        let provider_type = *PROVIDER_TYPE_MAP.get(provider.as_str()).ok_or(
+            ConfigurationError::ProviderNotSupported {
+                provider: provider.to_owned(),
+            },
+        )?;
+
+        check_credentials(loader.get_config()?, provider_type)?;
+
+        provider_types.push(provider_type);
+    }
+
+    loader.set_priority(provider_types)?;
+    loader.save_config()?;
+
+    println!("Success! Provider priority is set");
+
+    Ok(())
+}
+
+/// Set weights of providers in ensemble forecast, each one in format <provider>=<number>
+pub fn set_weights<Loader: ProviderLoader>(
+    weights: &[String],
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    for weight in weights {
+        let (provider_type, value) = parse_weight(weight)?;
+
+        // Weighted provider counts as configured, so it is compared with the others too
+        check_credentials(loader.get_config()?, provider_type)?;
+
+        loader.set_weight(provider_type, value)?;
+    }
+
+    loader.save_config()?;
+
+    println!("Success! Weights of providers are set");
+
+    Ok(())
+}
+
+/// Parse weight of provider in format <provider>=<number>,
+/// infinite weight would outweigh the others entirely and NaN would spoil the mean
+fn parse_weight(weight: &str) -> Result<(ProviderType, f64), ConfigurationError> {
+    let invalid = || ConfigurationError::InvalidWeight {
+        weight: weight.to_owned(),
+    };
+
+    let (provider, value) = weight.split_once('=').ok_or_else(invalid)?;
+    let value = value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .ok_or_else(invalid)?;
+
+    let provider_type = *PROVIDER_TYPE_MAP.get(provider.trim()).ok_or(
+        ConfigurationError::ProviderNotSupported {
+            provider: provider.to_owned(),
+        },
+    )?;
+
+    Ok((provider_type, value))
+}
+
+/// Set default unit system
+pub fn set_units<Loader: ProviderLoader>(
+    units: Units,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    loader.set_units(units)?;
+    loader.save_config()?;
+
+    println!("Success! Unit system {units:?} is default now");
+
+    Ok(())
+}
+
+/// Check that provider has API key and contact if it needs them, otherwise it would always fail
+fn check_credentials(config: &Config, provider: ProviderType) -> Result<(), ConfigurationError> {
+    if provider.requires_key() && config.keys.get(&provider).is_none() {
+        return Err(ConfigurationError::NotHasAPIKey {
+            provider: format!("{provider:?}"),
+        });
+    }
+    if provider.requires_contact() && config.contacts.get(&provider).is_none() {
+        return Err(ConfigurationError::NotHasContact {
+            provider: format!("{provider:?}"),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights() {
+        let cases = [
+            ("WeatherApi=2", Some((ProviderType::WeatherApi, 2.0))),
+            (" OpenMeteo = 0.5 ", Some((ProviderType::OpenMeteo, 0.5))),
+            ("Nws=0", Some((ProviderType::Nws, 0.0))),
+            ("WeatherApi=-1", None),
+            ("WeatherApi=inf", None),
+            ("WeatherApi=-inf", None),
+            ("WeatherApi=NaN", None),
+            ("WeatherApi=1e309", None),
+            ("WeatherApi", None),
+            ("WeatherApi=heavy", None),
+            ("Unknown=1", None),
+        ];
+
+        for (weight, expected) in cases {
+            assert_eq!(parse_weight(weight).ok(), expected, "weight {weight}");
+        }
+    }
+
+    #[test]
+    fn credentials() {
+        let config = Config {
+            keys: [(ProviderType::WeatherApi, "key".to_owned())].into(),
+            contacts: [(ProviderType::Nws, "me@example.com".to_owned())].into(),
+            ..Default::default()
+        };
+
+        assert!(check_credentials(&config, ProviderType::WeatherApi).is_ok());
+        assert!(check_credentials(&config, ProviderType::OpenMeteo).is_ok());
+        assert!(check_credentials(&config, ProviderType::Nws).is_ok());
+        assert!(matches!(
+            check_credentials(&config, ProviderType::TomorrowIo),
+            Err(ConfigurationError::NotHasAPIKey { .. })
+        ));
+        assert!(matches!(
+            check_credentials(&config, ProviderType::MetNorway),
+            Err(ConfigurationError::NotHasContact { .. })
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use eyre::Result;
+use std::io::{stderr, stdin, IsTerminal, Write};
+use thiserror::Error;
+
+use crate::{
+    location::{Location, Place},
+    provider::Geocoder,
+    provider_loader::ProviderLoader,
+};
+
+/// Errors for action geocode
+#[derive(Error, Debug)]
+pub enum GeocodingError {
+    #[error("Places are not found for {name}")]
+    PlaceNotFound { name: String },
+    #[error("Address {address} is ambiguous, use coordinates of one of the places:\n{candidates}")]
+    AmbiguousAddress { address: String, candidates: String },
+    #[error("Place number {choice} is invalid")]
+    InvalidChoice { choice: String },
+    #[error("Address {address} can not be resolved, none of the configured providers finds places, use coordinates instead")]
+    GeocoderNotConfigured { address: String },
+    #[error("Failed to read place number with error '{error}'")]
+    FailedToRead { error: std::io::Error },
+}
+
+/// Print places matching the name
+pub fn geocode<Loader: ProviderLoader>(
+    name: &str,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let provider = loader.get_default_provider()?;
+
+    let places = provider.geocode(name)?;
+    if places.is_empty() {
+        return Err(GeocodingError::PlaceNotFound {
+            name: name.to_owned(),
+        }
+        .into());
+    }
+
+    print!("Places found for {name}:\n{}", numbered(&places));
+
+    Ok(())
+}
+
+/// Resolve address to coordinates of the matching place,
+/// if there are several places, the user chooses one of them (or gets error if not interactive)
+pub fn resolve_location(provider: &impl Geocoder, location: &Location) -> Result<Location> {
+    let Location::Address(address) = location else {
+        return Ok(location.clone());
+    };
+
+    // Providers may return the same place several times with slightly different coordinates
+    let mut places: Vec<Place> = Vec::new();
+    for place in provider.geocode(address)? {
+        if !places.iter().any(|other| {
+            other.name == place.name
+                && other.region == place.region
+                && other.country == place.country
+        }) {
+            places.push(place);
+        }
+    }
+
+    match places.as_slice() {
+        // Let provider deal with unknown address by itself
+        [] => Ok(location.clone()),
+        [place] => Ok(place.to_location()),
+        places if stdin().is_terminal() && stderr().is_terminal() => {
+            Ok(choose(address, places)?.to_location())
+        }
+        places => Err(GeocodingError::AmbiguousAddress {
+            address: address.to_owned(),
+            candidates: numbered(places).trim_end().to_owned(),
+        }
+        .into()),
+    }
+}
+
+/// Ask the user to choose one of the places
+fn choose<'a>(address: &str, places: &'a [Place]) -> Result<&'a Place> {
+    eprint!(
+        "Address {address} is ambiguous:\n{}Choose place [1-{}]: ",
+        numbered(places),
+        places.len()
+    );
+    stderr()
+        .flush()
+        .map_err(|error| GeocodingError::FailedToRead { error })?;
+
+    let mut choice = String::new();
+    stdin()
+        .read_line(&mut choice)
+        .map_err(|error| GeocodingError::FailedToRead { error })?;
+
+    let place = choice
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| places.get(number.checked_sub(1)?))
+        .ok_or(GeocodingError::InvalidChoice {
+            choice: choice.trim().to_owned(),
+        })?;
+
+    Ok(place)
+}
+
+/// Format places as numbered list
+fn numbered(places: &[Place]) -> String {
+    places
+        .iter()
+        .enumerate()
+        .map(|(index, place)| format!("{}. {place}\n", index + 1))
+        .collect()
+}
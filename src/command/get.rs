@@ -1,60 +1,610 @@
-use chrono::{Local, NaiveDate, ParseError};
+use chrono::{Duration, FixedOffset, NaiveDate};
 use eyre::Result;
+use std::{panic::resume_unwind, slice, thread};
 use thiserror::Error;
 
-use crate::{provider::WeatherProvider, provider_loader::ProviderLoader};
+use super::geocode::{resolve_location, GeocodingError};
+use crate::{
+    astronomy::Astronomy,
+    cache::{CacheMode, CachedProvider},
+    date::{self, DATE_FORMS, MAX_OFFSET_DAYS},
+    forecast::{ComparisonTable, Forecast, ForecastTable},
+    location::Location,
+    output::{print_reports, OutputFormat, Report},
+    provider::ProviderType,
+    provider_loader::ProviderLoader,
+    units::Units,
+};
 
 /// Errors for action get
 #[derive(Error, Debug)]
 pub enum GettingError {
-    #[error("Date {date} is in unsupported format with error '{error}'")]
-    InvalidDate { error: ParseError, date: String },
-    #[error("Date {date} should be >= now")]
+    #[error("Date {date} is in unsupported format, use one of: {DATE_FORMS}")]
+    InvalidDate { date: String },
+    #[error("Date {date} should be >= now, past weather is available only for a single date")]
     PastDate { date: String },
+    #[error("Range end {to} should be >= range start {from}")]
+    InvalidRange { from: String, to: String },
+    #[error("Number of days should be > 0")]
+    ZeroDays,
+    #[error("Number of days should be <= {MAX_OFFSET_DAYS}")]
+    TooManyDays,
+    #[error("Range of {days} days from {from} ends beyond supported dates")]
+    RangeOverflow { from: String, days: u32 },
+    #[error("All providers failed:\n{failures}")]
+    AllProvidersFailed { failures: String },
 }
 
-/// Get weather information for provided address and date (none => now)
+/// Options of get command shared by all its modes
+#[derive(Clone, Copy)]
+pub struct GetOptions {
+    pub output: OutputFormat,
+    /// Unit system, none => from config
+    pub units: Option<Units>,
+    pub cache: CacheMode,
+    /// Provider to use instead of the configured ones
+    pub provider: Option<ProviderType>,
+    /// UTC offset to count dates in instead of the one of the location
+    pub tz: Option<FixedOffset>,
+}
+
+/// Get weather information for provided address and date (none => now),
+/// with current air quality if it is requested
 pub fn get<Loader: ProviderLoader>(
-    address: &str,
+    location: &Location,
     date: Option<&str>,
+    air_quality: bool,
+    options: GetOptions,
     loader_args: Loader::ConstructorArg<'_>,
 ) -> Result<()> {
     let mut loader = Loader::new(loader_args)?;
     loader.load_config()?;
 
-    let provider = loader.get_default_provider()?;
+    let units = options.units.unwrap_or(loader.get_config()?.units);
 
-    let now = Local::now().naive_local().date();
+    // Relative date is resolved in the timezone of the location, here it is only validated
+    date.map(validate_date).transpose()?;
 
-    // Parse and validate provided date
-    let date = date
-        .map(|date_str| {
-            NaiveDate::parse_from_str(date_str, "%d.%m.%Y").map_err(|error| {
-                GettingError::InvalidDate {
-                    error,
-                    date: date_str.to_owned(),
-                }
+    let resolved = resolve_once(&loader, options, units, location)?;
+
+    let ((weather, date, offset, agreement), provider) =
+        with_fallback(&loader, options, units, |cached| {
+            let location = &resolved;
+            let offset = use_timezone(cached, location, options.tz);
+            let today = date::today(offset);
+
+            let date = date
+                .map(|date| parse_date(date, today))
+                .transpose()?
+                .filter(|date| *date != today);
+
+            let weather = match date {
+                Some(date) if date < today => cached.get_history(location, &date, offset)?,
+                date => cached.get_weather(location, date.as_ref(), offset)?,
+            };
+            let date = date.unwrap_or(today);
+
+            Ok((
+                [with_sun(weather, location, &date, offset)],
+                date,
+                offset,
+                cached.take_agreement(),
+            ))
+        })?;
+
+    // Only some providers measure air quality, so it can come from another one
+    let air_quality = match air_quality {
+        true => Some(with_fallback(&loader, options, units, |cached| {
+            cached.get_air_quality(&resolved)
+        })?),
+        false => None,
+    };
+    let air_quality_text = air_quality
+        .as_ref()
+        .map_or(String::new(), |(air_quality, provider)| {
+            format!("\nAir quality ({provider:?}):\n{air_quality}")
+        });
+
+    // Ensemble tells how much its members agree on the forecast
+    let agreement_text = agreement
+        .iter()
+        .map(ToString::to_string)
+        .collect::<String>();
+
+    Report::new(&location.to_string(), provider, units, date, &weather)
+        .with_agreement(&agreement)
+        .with_air_quality(air_quality.as_ref().map(|(air_quality, _)| air_quality))
+        .print(
+            options.output,
+            format_args!(
+                "Weather information for {location} on {date} {timezone} ({provider:?}):\n{weather}{agreement_text}{air_quality_text}",
+                date = date.format("%d.%m.%Y"),
+                timezone = date::format_utc_offset(offset),
+                weather = weather[0]
+            ),
+        )?;
+
+    Ok(())
+}
+
+/// Get weather information from all configured providers on the date (none => now) side by side
+pub fn get_all<Loader: ProviderLoader>(
+    location: &Location,
+    date: Option<&str>,
+    options: GetOptions,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let units = options.units.unwrap_or(loader.get_config()?.units);
+
+    // Relative date is resolved in the timezone of the location, here it is only validated
+    date.map(validate_date).transpose()?;
+
+    // Providers that cannot be created (e.g. without API key) fail on their own
+    let mut failures = Vec::new();
+    let providers = loader
+        .get_configured_providers()?
+        .into_iter()
+        .filter_map(|provider| match loader.get_provider(provider) {
+            Ok(provider) => Some(provider),
+            Err(error) => {
+                failures.push((provider, error));
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Address and timezone are resolved once, so the user chooses the place only once,
+    // the timezone is taken from the first provider that knows it, the default one is not needed
+    let resolved = resolve_once(&loader, options, units, location)?;
+    let offset = options
+        .tz
+        .or_else(|| {
+            providers.iter().find_map(|provider| {
+                CachedProvider::new(provider, units, options.cache)
+                    .get_utc_offset(&resolved)
+                    .ok()
             })
         })
+        .or_else(|| estimate_timezone(&resolved))
+        .unwrap_or_else(date::local_offset);
+    let now = date::today(offset);
+
+    let date = date
+        .map(|date| parse_date(date, now))
         .transpose()?
-        .map(|date| {
-            if date < now {
-                Err(GettingError::PastDate {
-                    date: date.format("%d.%m.%Y").to_string(),
-                })
-            } else {
-                Ok(date)
+        .filter(|date| *date != now);
+
+    let results = thread::scope(|scope| {
+        let resolved = &resolved;
+        let date = date.as_ref();
+
+        let handles = providers
+            .iter()
+            .map(|provider| {
+                let handle = scope.spawn(move || {
+                    let cached = CachedProvider::new(provider, units, options.cache);
+                    let forecast = match date {
+                        Some(date) if *date < now => cached.get_history(resolved, date, offset)?,
+                        date => cached.get_weather(resolved, date, offset)?,
+                    };
+
+                    Ok((origin_provider(&cached), forecast))
+                });
+
+                (provider.provider_type(), handle)
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|(provider, handle)| {
+                let result: Result<(ProviderType, Forecast)> =
+                    handle.join().unwrap_or_else(|panic| resume_unwind(panic));
+
+                (provider, result)
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut forecasts = Vec::new();
+    for (provider, result) in results {
+        match result {
+            Ok(forecast) => forecasts.push(forecast),
+            Err(error) => failures.push((provider, error)),
+        }
+    }
+
+    if forecasts.is_empty() {
+        return Err(all_failed(failures));
+    }
+    for (provider, error) in &failures {
+        eprintln!("Provider {provider:?} failed with error '{error}'");
+    }
+
+    let date = date.unwrap_or(now);
+    let address = location.to_string();
+    let reports = forecasts
+        .iter()
+        .map(|(provider, forecast)| {
+            Report::new(&address, *provider, units, date, slice::from_ref(forecast))
+        })
+        .collect::<Vec<_>>();
+
+    print_reports(
+        &reports,
+        options.output,
+        format_args!(
+            "Weather information for {location} on {date} {timezone} from all providers:\n{table}",
+            date = date.format("%d.%m.%Y"),
+            timezone = date::format_utc_offset(offset),
+            table = ComparisonTable(&forecasts)
+        ),
+    )?;
+
+    Ok(())
+}
+
+/// Get weather information for provided address for each day of the range
+/// which is set by its first date (none => today) and either last date or number of days
+pub fn get_range<Loader: ProviderLoader>(
+    location: &Location,
+    from: Option<&str>,
+    to: Option<&str>,
+    days: Option<u32>,
+    options: GetOptions,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let units = options.units.unwrap_or(loader.get_config()?.units);
+
+    // Relative dates are resolved in the timezone of the location, here they are only validated
+    from.map(validate_date).transpose()?;
+    to.map(validate_date).transpose()?;
+    match days {
+        Some(0) => return Err(GettingError::ZeroDays.into()),
+        Some(days) if i64::from(days) > MAX_OFFSET_DAYS => {
+            return Err(GettingError::TooManyDays.into())
+        }
+        _ => {}
+    }
+
+    let resolved = resolve_once(&loader, options, units, location)?;
+
+    let ((forecasts, from, to, offset, agreement), provider) =
+        with_fallback(&loader, options, units, |cached| {
+            let location = &resolved;
+            let offset = use_timezone(cached, location, options.tz);
+            let today = date::today(offset);
+
+            let from = match from {
+                Some(from) => not_past(parse_date(from, today)?, today)?,
+                None => today,
+            };
+
+            let to = match (to, days) {
+                (Some(to), _) => parse_date(to, today)?,
+                (None, Some(days)) => range_end(from, days)?,
+                (None, None) => from,
+            };
+
+            if to < from {
+                return Err(GettingError::InvalidRange {
+                    from: from.format("%d.%m.%Y").to_string(),
+                    to: to.format("%d.%m.%Y").to_string(),
+                }
+                .into());
             }
+
+            let forecasts = cached
+                .get_forecast_range(location, &from, &to, offset)?
+                .into_iter()
+                .map(|forecast| with_sun(forecast, location, &from, offset))
+                .collect::<Vec<_>>();
+
+            Ok((forecasts, from, to, offset, cached.take_agreement()))
+        })?;
+
+    Report::new(&location.to_string(), provider, units, from, &forecasts)
+        .with_agreement(&agreement)
+        .print(
+        options.output,
+        format_args!(
+            "Weather information for {location} from {from} to {to} {timezone} ({provider:?}):\n{table}",
+            from = from.format("%d.%m.%Y"),
+            to = to.format("%d.%m.%Y"),
+            timezone = date::format_utc_offset(offset),
+            table = ForecastTable(&forecasts)
+        ),
+    )?;
+
+    Ok(())
+}
+
+/// Last date of the range of days starting from the date
+fn range_end(from: NaiveDate, days: u32) -> Result<NaiveDate, GettingError> {
+    from.checked_add_signed(Duration::days(i64::from(days) - 1))
+        .ok_or_else(|| GettingError::RangeOverflow {
+            from: from.format("%d.%m.%Y").to_string(),
+            days,
         })
-        .transpose()?
-        .filter(|date| *date != now);
+}
+
+/// Get hourly weather information for provided address and date (none => today)
+pub fn get_hourly<Loader: ProviderLoader>(
+    location: &Location,
+    date: Option<&str>,
+    options: GetOptions,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
 
-    let weather = provider.get_weather(address, date.as_ref())?;
+    let units = options.units.unwrap_or(loader.get_config()?.units);
 
-    println!(
-        "Weather information for {address} on {date}:\n{weather}",
-        date = date.unwrap_or(now).format("%d.%m.%Y")
-    );
+    // Relative date is resolved in the timezone of the location, here it is only validated
+    date.map(validate_date).transpose()?;
+
+    let resolved = resolve_once(&loader, options, units, location)?;
+
+    let ((forecasts, date, offset, agreement), provider) =
+        with_fallback(&loader, options, units, |cached| {
+            let location = &resolved;
+            let offset = use_timezone(cached, location, options.tz);
+            let today = date::today(offset);
+
+            let date = match date {
+                Some(date) => not_past(parse_date(date, today)?, today)?,
+                None => today,
+            };
+
+            Ok((
+                cached.get_hourly(location, &date, offset)?,
+                date,
+                offset,
+                cached.take_agreement(),
+            ))
+        })?;
+
+    Report::new(&location.to_string(), provider, units, date, &forecasts)
+        .with_agreement(&agreement)
+        .print(
+        options.output,
+        format_args!(
+            "Hourly weather information for {location} on {date} {timezone} ({provider:?}):\n{table}",
+            date = date.format("%d.%m.%Y"),
+            timezone = date::format_utc_offset(offset),
+            table = ForecastTable(&forecasts)
+        ),
+    )?;
 
     Ok(())
 }
+
+/// Request forecasts from providers in priority order until one of them succeeds
+/// (only from the provider of options if it is set),
+/// gives the forecasts with the provider that gave them
+pub fn with_fallback<Loader: ProviderLoader, T>(
+    loader: &Loader,
+    options: GetOptions,
+    units: Units,
+    request: impl Fn(&CachedProvider) -> Result<T>,
+) -> Result<(T, ProviderType)> {
+    let chain = match options.provider {
+        Some(provider) => vec![provider],
+        None => loader.get_provider_chain()?,
+    };
+
+    let mut failures = Vec::new();
+
+    for provider_type in chain {
+        let result = loader.get_provider(provider_type).and_then(|provider| {
+            let cached = CachedProvider::new(&provider, units, options.cache);
+            let data = request(&cached)?;
+
+            Ok((data, origin_provider(&cached)))
+        });
+
+        match result {
+            Ok(result) => {
+                for (provider, error) in &failures {
+                    eprintln!("Provider {provider:?} failed with error '{error}'");
+                }
+
+                return Ok(result);
+            }
+            // Other providers would not resolve the address better, nor accept invalid dates
+            Err(error) if error.is::<GeocodingError>() || error.is::<GettingError>() => {
+                return Err(error)
+            }
+            Err(error) => failures.push((provider_type, error)),
+        }
+    }
+
+    Err(all_failed(failures))
+}
+
+/// Resolve address once before requesting providers, with the first provider of the chain
+/// that can geocode (or any other configured one), so all providers get the same coordinates
+/// and the user chooses the place only once
+pub fn resolve_once<Loader: ProviderLoader>(
+    loader: &Loader,
+    options: GetOptions,
+    units: Units,
+    location: &Location,
+) -> Result<Location> {
+    let Location::Address(address) = location else {
+        return Ok(location.clone());
+    };
+
+    // Without the default provider there is no chain, but configured providers can still geocode
+    let mut geocoders = match (options.provider, loader.get_config()?.default) {
+        (Some(provider), _) => vec![provider],
+        (None, Some(_)) => loader.get_provider_chain()?,
+        (None, None) => Vec::new(),
+    };
+    for provider in loader.get_configured_providers()? {
+        if !geocoders.contains(&provider) {
+            geocoders.push(provider);
+        }
+    }
+    geocoders.retain(|provider| provider.can_geocode());
+
+    let mut failures = Vec::new();
+
+    for provider_type in geocoders {
+        let result = loader.get_provider(provider_type).and_then(|provider| {
+            resolve_location(
+                &CachedProvider::new(&provider, units, options.cache),
+                location,
+            )
+        });
+
+        match result {
+            Ok(location) => return Ok(location),
+            // Other providers would not make the address less ambiguous
+            Err(error) if error.is::<GeocodingError>() => return Err(error),
+            Err(error) => failures.push((provider_type, error)),
+        }
+    }
+
+    match failures.is_empty() {
+        true => Err(GeocodingError::GeocoderNotConfigured {
+            address: address.to_owned(),
+        }
+        .into()),
+        false => Err(all_failed(failures)),
+    }
+}
+
+/// Error for failures of all providers
+fn all_failed(mut failures: Vec<(ProviderType, eyre::Report)>) -> eyre::Report {
+    // Error of the only provider is clear enough by itself
+    if failures.len() == 1 {
+        return failures.remove(0).1;
+    }
+
+    GettingError::AllProvidersFailed {
+        failures: failures
+            .iter()
+            .map(|(provider, error)| format!("{provider:?}: {error}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+    .into()
+}
+
+/// Provider of the last forecasts, warns if they are stored ones
+fn origin_provider(cached: &CachedProvider) -> ProviderType {
+    match cached.origin() {
+        Some(origin) => {
+            eprintln!("Showing last known forecast fetched by {origin}");
+            origin.provider
+        }
+        None => cached.provider_type(),
+    }
+}
+
+/// UTC offset to count dates in: the one of options or the one of the location if the provider reports it
+pub fn use_timezone(
+    cached: &CachedProvider,
+    location: &Location,
+    tz: Option<FixedOffset>,
+) -> FixedOffset {
+    // Dates of the location with unknown timezone are counted in the local timezone of the machine
+    tz.or_else(|| cached.get_utc_offset(location).ok())
+        .unwrap_or_else(date::local_offset)
+}
+
+/// UTC offset of the location estimated from its longitude if no provider knows its timezone,
+/// none => the location is not set by coordinates
+pub fn estimate_timezone(location: &Location) -> Option<FixedOffset> {
+    let Location::Coordinates { lon, .. } = location else {
+        return None;
+    };
+    let offset = date::estimate_utc_offset(*lon)?;
+
+    eprintln!(
+        "Timezone is estimated from longitude as {}, set it with --tz",
+        date::format_utc_offset(offset)
+    );
+
+    Some(offset)
+}
+
+/// Fill unknown sunrise and sunset of the daily forecast (or current weather on the date)
+/// with the calculated ones if the location is set by coordinates
+fn with_sun(
+    forecast: Forecast,
+    location: &Location,
+    date: &NaiveDate,
+    offset: FixedOffset,
+) -> Forecast {
+    let date = forecast.date.unwrap_or(*date);
+    let Some(astronomy) = Astronomy::calculate_at(location, &date, offset) else {
+        return forecast;
+    };
+
+    Forecast {
+        sunrise: forecast.sunrise.or(astronomy.sunrise),
+        sunset: forecast.sunset.or(astronomy.sunset),
+        ..forecast
+    }
+}
+
+/// Parse absolute or relative date (resolved against today), it can be in the past
+pub fn parse_date(date_str: &str, today: NaiveDate) -> Result<NaiveDate, GettingError> {
+    date::parse_date(date_str, today).ok_or(GettingError::InvalidDate {
+        date: date_str.to_owned(),
+    })
+}
+
+/// Check that the date is in one of the accepted forms before the timezone of the location is known
+pub fn validate_date(date_str: &str) -> Result<(), GettingError> {
+    parse_date(date_str, date::today(date::local_offset())).map(|_| ())
+}
+
+/// Check that the date is not before today
+fn not_past(date: NaiveDate, today: NaiveDate) -> Result<NaiveDate, GettingError> {
+    if date < today {
+        Err(GettingError::PastDate {
+            date: date.format("%d.%m.%Y").to_string(),
+        })
+    } else {
+        Ok(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_date_lists_supported_forms() {
+        let error = validate_date("someday").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            format!("Date someday is in unsupported format, use one of: {DATE_FORMS}")
+        );
+    }
+
+    #[test]
+    fn range_end_does_not_overflow() {
+        let from = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+
+        assert_eq!(range_end(from, 1).unwrap(), from);
+        assert_eq!(
+            range_end(from, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+        );
+        assert!(matches!(
+            range_end(NaiveDate::MAX, 2),
+            Err(GettingError::RangeOverflow { days: 2, .. })
+        ));
+    }
+}
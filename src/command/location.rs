@@ -0,0 +1,107 @@
+use eyre::Result;
+use thiserror::Error;
+
+use crate::{location::Location, provider_loader::ProviderLoader};
+
+/// Errors for action location
+#[derive(Error, Debug)]
+pub enum SavedLocationError {
+    #[error("Location {name} is not saved")]
+    LocationNotFound { name: String },
+    #[error("Location is not set, pass it explicitly or save the default one")]
+    DefaultLocationNotSet,
+    #[error("Location to save is not set")]
+    LocationNotSet,
+}
+
+/// Save location under the name, optionally making it the default one
+pub fn add_location<Loader: ProviderLoader>(
+    name: &str,
+    location: Option<Location>,
+    default: bool,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let location = location.ok_or(SavedLocationError::LocationNotSet)?;
+
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    loader.add_location(name, location)?;
+    if default {
+        loader.set_default_location(Some(name))?;
+    }
+    loader.save_config()?;
+
+    println!("Success! Location {name} is saved");
+
+    Ok(())
+}
+
+/// Remove saved location
+pub fn remove_location<Loader: ProviderLoader>(
+    name: &str,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    if !loader.get_config()?.locations.contains_key(name) {
+        return Err(SavedLocationError::LocationNotFound {
+            name: name.to_owned(),
+        }
+        .into());
+    }
+
+    loader.remove_location(name)?;
+    loader.save_config()?;
+
+    println!("Success! Location {name} is removed");
+
+    Ok(())
+}
+
+/// Print saved locations
+pub fn list_locations<Loader: ProviderLoader>(
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<()> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let config = loader.get_config()?;
+    for (name, location) in &config.locations {
+        let default = if config.default_location.as_ref() == Some(name) {
+            " (default)"
+        } else {
+            ""
+        };
+
+        println!("{name}: {location}{default}");
+    }
+
+    Ok(())
+}
+
+/// Get saved location by the name or the default one
+pub fn saved_location<Loader: ProviderLoader>(
+    name: Option<&str>,
+    loader_args: Loader::ConstructorArg<'_>,
+) -> Result<Location> {
+    let mut loader = Loader::new(loader_args)?;
+    loader.load_config()?;
+
+    let config = loader.get_config()?;
+    let name = name
+        .or(config.default_location.as_deref())
+        .ok_or(SavedLocationError::DefaultLocationNotSet)?;
+
+    let location =
+        config
+            .locations
+            .get(name)
+            .cloned()
+            .ok_or(SavedLocationError::LocationNotFound {
+                name: name.to_owned(),
+            })?;
+
+    Ok(location)
+}
@@ -1,11 +1,30 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::provider::ProviderType;
+use crate::{location::Location, provider::ProviderType, units::Units};
 
-/// Config that consists default provider and API keys
-#[derive(Serialize, Deserialize)]
+/// Config that consists default provider, API keys, contacts, base URL overrides, unit system,
+/// named locations, providers to fall back to and weights of providers in ensemble
+#[derive(Serialize, Deserialize, Default)]
 pub struct Config {
     pub default: Option<ProviderType>,
+    /// API keys of providers, providers without API key have empty one once they are configured
     pub keys: HashMap<ProviderType, String>,
+    /// Contacts (e.g. email) of the user sent to providers that require identification
+    #[serde(default)]
+    pub contacts: HashMap<ProviderType, String>,
+    #[serde(default)]
+    pub base_urls: HashMap<ProviderType, String>,
+    #[serde(default)]
+    pub units: Units,
+    #[serde(default)]
+    pub locations: BTreeMap<String, Location>,
+    #[serde(default)]
+    pub default_location: Option<String>,
+    /// Providers to try in order if the default one fails
+    #[serde(default)]
+    pub priority: Vec<ProviderType>,
+    /// Weights of providers in ensemble forecast, missing => 1
+    #[serde(default)]
+    pub weights: HashMap<ProviderType, f64>,
 }
@@ -0,0 +1,212 @@
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Utc, Weekday,
+};
+
+/// Accepted forms of date for messages
+pub const DATE_FORMS: &str =
+    "dd.mm.yyyy, yyyy-mm-dd, today, tomorrow, yesterday, +<N>d, -<N>d, <weekday>, next <weekday>";
+
+/// Accepted forms of timezone for messages
+pub const TIMEZONE_FORMS: &str = "UTC, +hh:mm, -hh:mm, +hhmm, +hh, UTC+hh:mm";
+
+/// The largest offset in days, chrono panics on durations out of its range
+pub const MAX_OFFSET_DAYS: i64 = 100_000;
+
+/// Current UTC offset of the local timezone of the machine
+pub fn local_offset() -> FixedOffset {
+    *Local::now().offset()
+}
+
+/// Current time in the timezone with the UTC offset
+pub fn now(offset: FixedOffset) -> DateTime<FixedOffset> {
+    Utc::now().with_timezone(&offset)
+}
+
+/// Current date in the timezone with the UTC offset
+pub fn today(offset: FixedOffset) -> NaiveDate {
+    now(offset).date_naive()
+}
+
+/// Convert UTC time to the timezone with the UTC offset
+pub fn to_local(time: &DateTime<Utc>, offset: FixedOffset) -> NaiveDateTime {
+    time.with_timezone(&offset).naive_local()
+}
+
+/// Estimate UTC offset of the location from its longitude by the nautical timezone (15° per hour),
+/// real timezones follow borders and daylight saving time, so it can be an hour or two off
+pub fn estimate_utc_offset(lon: f64) -> Option<FixedOffset> {
+    let hours = (lon / 15.0).round();
+
+    (-12.0..=12.0)
+        .contains(&hours)
+        .then(|| FixedOffset::east_opt(hours as i32 * 3600))
+        .flatten()
+}
+
+/// Format UTC offset for messages (e.g. UTC+09:00)
+pub fn format_utc_offset(offset: FixedOffset) -> String {
+    format!("UTC{offset}")
+}
+
+/// Parse UTC offset of timezone in one of the accepted forms (case insensitive):
+/// - UTC or Z
+/// - +hh:mm, +hhmm or +hh (and the same with -), optionally prefixed with UTC
+pub fn parse_utc_offset(text: &str) -> Option<FixedOffset> {
+    let text = text.trim().to_uppercase();
+    let offset = text.strip_prefix("UTC").unwrap_or(&text);
+
+    if offset.is_empty() || offset == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, offset) = match (offset.strip_prefix('+'), offset.strip_prefix('-')) {
+        (Some(offset), _) => (1, offset),
+        (_, Some(offset)) => (-1, offset),
+        _ => return None,
+    };
+
+    let (hours, minutes) = match (offset.split_once(':'), offset.len()) {
+        (Some((hours, minutes)), _) => (hours, minutes),
+        (None, 4) if offset.is_ascii() => offset.split_at(2),
+        (None, 1 | 2) => (offset, "0"),
+        _ => return None,
+    };
+
+    // Offsets of real timezones are in range [-12:00, +14:00]
+    let hours = hours.parse::<u8>().ok().filter(|hours| *hours <= 14)?;
+    let minutes = minutes.parse::<u8>().ok().filter(|minutes| *minutes < 60)?;
+
+    FixedOffset::east_opt(sign * (i32::from(hours) * 3600 + i32::from(minutes) * 60))
+}
+
+/// Parse date in one of the accepted forms (case insensitive), relative ones are resolved against today:
+/// - dd.mm.yyyy or yyyy-mm-dd (ISO 8601)
+/// - today, tomorrow, yesterday
+/// - +<N>d or -<N>d => N days after or before today
+/// - weekday (e.g. friday, fri) => the nearest one starting from today
+/// - next <weekday> => the nearest one after today
+pub fn parse_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let text = text.trim().to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%d.%m.%Y")
+        .or_else(|_| NaiveDate::parse_from_str(&text, "%Y-%m-%d"))
+    {
+        return Some(date);
+    }
+
+    match text.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        _ => {}
+    }
+
+    if let Some(days) = text
+        .strip_suffix('d')
+        .filter(|days| days.starts_with(['+', '-']))
+    {
+        let days = days
+            .parse::<i64>()
+            .ok()
+            .filter(|days| days.abs() <= MAX_OFFSET_DAYS)?;
+
+        return today.checked_add_signed(Duration::days(days));
+    }
+
+    let (after_today, weekday) = match text.strip_prefix("next ") {
+        Some(weekday) => (true, weekday.trim()),
+        None => (false, text.as_str()),
+    };
+    let weekday = weekday.parse::<Weekday>().ok()?;
+
+    let days =
+        match (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7 {
+            0 if after_today => 7,
+            days => days,
+        };
+
+    today.checked_add_signed(Duration::days(i64::from(days)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    #[test]
+    fn dates() {
+        // Saturday
+        let today = date(2026, 10, 17).unwrap();
+
+        let cases = [
+            // Absolute dates
+            ("20.10.2026", date(2026, 10, 20)),
+            ("2026-10-20", date(2026, 10, 20)),
+            (" 01.01.2025 ", date(2025, 1, 1)),
+            ("31.02.2026", None),
+            ("2026-13-01", None),
+            // Named days
+            ("today", date(2026, 10, 17)),
+            ("Tomorrow", date(2026, 10, 18)),
+            ("yesterday", date(2026, 10, 16)),
+            // Days after or before today
+            ("+0d", date(2026, 10, 17)),
+            ("+3d", date(2026, 10, 20)),
+            ("+15d", date(2026, 11, 1)),
+            ("-2d", date(2026, 10, 15)),
+            ("3d", None),
+            ("+d", None),
+            ("+1000000d", None),
+            // The nearest weekday starting from today
+            ("saturday", date(2026, 10, 17)),
+            ("sunday", date(2026, 10, 18)),
+            ("FRI", date(2026, 10, 23)),
+            ("mon", date(2026, 10, 19)),
+            // The nearest weekday after today, the same weekday is a week later
+            ("next saturday", date(2026, 10, 24)),
+            ("next sunday", date(2026, 10, 18)),
+            ("next  friday", date(2026, 10, 23)),
+            ("next", None),
+            ("next week", None),
+            // Unsupported forms
+            ("", None),
+            ("someday", None),
+            ("10/20/2026", None),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(parse_date(text, today), expected, "date '{text}'");
+        }
+    }
+
+    #[test]
+    fn estimated_offsets() {
+        let hours = |hours: i32| FixedOffset::east_opt(hours * 3600);
+
+        let cases = [
+            (0.0, hours(0)),
+            (139.69, hours(9)),
+            (18.96, hours(1)),
+            (-74.0, hours(-5)),
+            (7.4, hours(0)),
+            (7.6, hours(1)),
+            // Zones of ±12 hours are half as wide at the date line
+            (172.4, hours(11)),
+            (172.5, hours(12)),
+            (180.0, hours(12)),
+            (-172.5, hours(-12)),
+            (-180.0, hours(-12)),
+            (187.5, None),
+            (-187.5, None),
+            (f64::NAN, None),
+            (400.0, None),
+        ];
+
+        for (lon, expected) in cases {
+            assert_eq!(estimate_utc_offset(lon), expected, "longitude {lon}");
+        }
+    }
+}
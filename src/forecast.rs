@@ -1,15 +1,308 @@
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-/// Representation of information about weather
+use crate::{provider::ProviderType, units::Units};
+
+/// Representation of information about weather,
+/// providers give it in metric units
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Forecast {
+    /// Unit system of the values
+    #[serde(skip)]
+    pub units: Units,
+    /// Date of the forecast, none => current weather
+    pub date: Option<NaiveDate>,
+    /// Time of the hourly forecast, none => forecast for the whole day
+    pub time: Option<NaiveTime>,
     pub temp: f64,
+    /// Lowest temperature of the day, present only in daily forecast
+    pub temp_min: Option<f64>,
+    /// Highest temperature of the day, present only in daily forecast
+    pub temp_max: Option<f64>,
     pub condition: String,
+    pub feels_like: Option<f64>,
+    /// Relative humidity in %
+    pub humidity: Option<f64>,
+    pub wind_speed: Option<f64>,
+    /// Wind direction in degrees (meteorological)
+    pub wind_direction: Option<f64>,
+    /// Atmospheric pressure in hPa
+    pub pressure: Option<f64>,
+    /// Cloud cover in %
+    pub clouds: Option<f64>,
+    /// Precipitation amount
+    pub precipitation: Option<f64>,
+    pub uv_index: Option<f64>,
+    /// Local time of sunrise, none => unknown or the sun does not rise
+    pub sunrise: Option<NaiveTime>,
+    /// Local time of sunset, none => unknown or the sun does not set
+    pub sunset: Option<NaiveTime>,
+}
+
+impl Forecast {
+    /// Convert metric forecast to the unit system
+    pub fn into_units(self, units: Units) -> Self {
+        Forecast {
+            units,
+            temp: units.temperature(self.temp),
+            temp_min: self.temp_min.map(|temp| units.temperature(temp)),
+            temp_max: self.temp_max.map(|temp| units.temperature(temp)),
+            feels_like: self.feels_like.map(|temp| units.temperature(temp)),
+            wind_speed: self.wind_speed.map(|speed| units.speed(speed)),
+            precipitation: self
+                .precipitation
+                .map(|precipitation| units.precipitation(precipitation)),
+            ..self
+        }
+    }
 }
 
 /// Pretty print for Forecast
 impl Display for Forecast {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Temperature: {}", self.temp)?;
-        writeln!(f, "Condition: {}", self.condition)
+        let temperature = self.units.temperature_label();
+        let speed = self.units.speed_label();
+
+        writeln!(f, "Temperature: {}{temperature}", self.temp)?;
+        if let Some(temp_min) = self.temp_min {
+            writeln!(f, "Min temperature: {temp_min}{temperature}")?;
+        }
+        if let Some(temp_max) = self.temp_max {
+            writeln!(f, "Max temperature: {temp_max}{temperature}")?;
+        }
+        if let Some(feels_like) = self.feels_like {
+            writeln!(f, "Feels like: {feels_like}{temperature}")?;
+        }
+        writeln!(f, "Condition: {}", self.condition)?;
+        if let Some(humidity) = self.humidity {
+            writeln!(f, "Humidity: {humidity}%")?;
+        }
+        match (self.wind_speed, self.wind_direction) {
+            (Some(wind), Some(direction)) => writeln!(f, "Wind: {wind} {speed}, {direction}°")?,
+            (Some(wind), None) => writeln!(f, "Wind: {wind} {speed}")?,
+            _ => {}
+        }
+        if let Some(pressure) = self.pressure {
+            writeln!(f, "Pressure: {pressure} hPa")?;
+        }
+        if let Some(clouds) = self.clouds {
+            writeln!(f, "Clouds: {clouds}%")?;
+        }
+        if let Some(precipitation) = self.precipitation {
+            writeln!(
+                f,
+                "Precipitation: {precipitation} {}",
+                self.units.precipitation_label()
+            )?;
+        }
+        if let Some(uv_index) = self.uv_index {
+            writeln!(f, "UV index: {uv_index}")?;
+        }
+        if let Some(sunrise) = self.sunrise {
+            writeln!(f, "Sunrise: {}", sunrise.format("%H:%M"))?;
+        }
+        if let Some(sunset) = self.sunset {
+            writeln!(f, "Sunset: {}", sunset.format("%H:%M"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Table of forecasts, one row per forecast
+pub struct ForecastTable<'a>(pub &'a [Forecast]);
+
+/// Pretty print for ForecastTable
+impl Display for ForecastTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<16} | {:>11} | {:>10} | {:<24} | {:>8} | {:>9} | {:>13}",
+            "Date", "Temperature", "Feels like", "Condition", "Humidity", "Wind", "Precipitation"
+        )?;
+
+        for forecast in self.0 {
+            let temperature = forecast.units.temperature_label();
+
+            writeln!(
+                f,
+                "{:<16} | {:>11} | {:>10} | {:<24} | {:>8} | {:>9} | {:>13}",
+                match (forecast.date, forecast.time) {
+                    (Some(date), Some(time)) =>
+                        format!("{} {}", date.format("%d.%m.%Y"), time.format("%H:%M")),
+                    (Some(date), None) => date.format("%d.%m.%Y").to_string(),
+                    (None, _) => "Now".to_owned(),
+                },
+                format!("{}{temperature}", forecast.temp),
+                cell(forecast.feels_like, temperature),
+                forecast.condition,
+                cell(forecast.humidity, "%"),
+                cell(
+                    forecast.wind_speed,
+                    &format!(" {}", forecast.units.speed_label())
+                ),
+                cell(
+                    forecast.precipitation,
+                    &format!(" {}", forecast.units.precipitation_label())
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Getter of numeric field of Forecast
+type Field = fn(&Forecast) -> Option<f64>;
+
+/// Forecasts of several providers side by side, one row per field with the spread of its values
+pub struct ComparisonTable<'a>(pub &'a [(ProviderType, Forecast)]);
+
+/// Pretty print for ComparisonTable
+impl Display for ComparisonTable<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((_, first)) = self.0.first() else {
+            return Ok(());
+        };
+
+        let temperature = first.units.temperature_label();
+        let speed = format!(" {}", first.units.speed_label());
+        let precipitation = format!(" {}", first.units.precipitation_label());
+
+        write!(f, "{:<14}", "Field")?;
+        for (provider, _) in self.0 {
+            write!(f, " | {:>14}", format!("{provider:?}"))?;
+        }
+        writeln!(f, " | {:>10}", "Spread")?;
+
+        write!(f, "{:<14}", "Condition")?;
+        for (_, forecast) in self.0 {
+            write!(f, " | {:>14}", forecast.condition)?;
+        }
+        let same = self
+            .0
+            .iter()
+            .all(|(_, forecast)| forecast.condition == first.condition);
+        writeln!(f, " | {:>10}", if same { "-" } else { "differs" })?;
+
+        let rows: [(&str, Field, &str); 9] = [
+            ("Temperature", |forecast| Some(forecast.temp), temperature),
+            ("Feels like", |forecast| forecast.feels_like, temperature),
+            ("Humidity", |forecast| forecast.humidity, "%"),
+            ("Wind", |forecast| forecast.wind_speed, &speed),
+            ("Wind direction", |forecast| forecast.wind_direction, "°"),
+            ("Pressure", |forecast| forecast.pressure, " hPa"),
+            ("Clouds", |forecast| forecast.clouds, "%"),
+            (
+                "Precipitation",
+                |forecast| forecast.precipitation,
+                &precipitation,
+            ),
+            ("UV index", |forecast| forecast.uv_index, ""),
+        ];
+
+        for (name, value, unit) in rows {
+            let values = self
+                .0
+                .iter()
+                .map(|(_, forecast)| value(forecast))
+                .collect::<Vec<_>>();
+
+            // Field that no provider gives is not interesting
+            if values.iter().all(Option::is_none) {
+                continue;
+            }
+
+            write!(f, "{name:<14}")?;
+            for value in &values {
+                write!(f, " | {:>14}", cell(*value, unit))?;
+            }
+            writeln!(f, " | {:>10}", cell(spread(&values), unit))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Difference between the largest and the smallest of known values, none => less than 2 values
+fn spread(values: &[Option<f64>]) -> Option<f64> {
+    let values = values.iter().flatten().copied().collect::<Vec<_>>();
+    if values.len() < 2 {
+        return None;
+    }
+
+    let max = values.iter().copied().fold(f64::MIN, f64::max);
+    let min = values.iter().copied().fold(f64::MAX, f64::min);
+
+    Some(((max - min) * 100.0).round() / 100.0)
+}
+
+/// Format optional value for table cell
+fn cell(value: Option<f64>, unit: &str) -> String {
+    value.map_or("-".to_owned(), |value| format!("{value}{unit}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forecast() -> Forecast {
+        Forecast {
+            temp: 20.0,
+            temp_min: Some(10.0),
+            temp_max: Some(25.0),
+            condition: "Clear".to_owned(),
+            feels_like: Some(18.5),
+            humidity: Some(60.0),
+            wind_speed: Some(5.0),
+            wind_direction: Some(270.0),
+            pressure: Some(1013.0),
+            precipitation: Some(12.7),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn units() {
+        let cases = [
+            // temp, temp_min, temp_max, feels_like, wind_speed, precipitation
+            (Units::Metric, [20.0, 10.0, 25.0, 18.5, 5.0, 12.7]),
+            (Units::Imperial, [68.0, 50.0, 77.0, 65.3, 11.18, 0.5]),
+            (Units::Standard, [293.15, 283.15, 298.15, 291.65, 5.0, 12.7]),
+        ];
+
+        for (units, expected) in cases {
+            let forecast = forecast().into_units(units);
+            let values = [
+                Some(forecast.temp),
+                forecast.temp_min,
+                forecast.temp_max,
+                forecast.feels_like,
+                forecast.wind_speed,
+                forecast.precipitation,
+            ];
+
+            assert_eq!(forecast.units, units);
+            assert_eq!(values, expected.map(Some), "{units:?}");
+            // Pressure is in hPa, humidity and direction are the same in all systems
+            assert_eq!(forecast.pressure, Some(1013.0), "{units:?}");
+            assert_eq!(forecast.humidity, Some(60.0), "{units:?}");
+            assert_eq!(forecast.wind_direction, Some(270.0), "{units:?}");
+        }
+    }
+
+    #[test]
+    fn labels_of_units() {
+        let text = forecast().into_units(Units::Imperial).to_string();
+
+        assert!(text.contains("Temperature: 68°F\n"));
+        assert!(text.contains("Wind: 11.18 mph, 270°\n"));
+        assert!(text.contains("Pressure: 1013 hPa\n"));
+        assert!(text.contains("Precipitation: 0.5 in\n"));
+
+        let text = forecast().into_units(Units::Standard).to_string();
+        assert!(text.contains("Temperature: 293.15K\n"));
+        assert!(text.contains("Wind: 5 m/s, 270°\n"));
     }
 }
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use thiserror::Error;
+
+/// Location to get weather for
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Location {
+    /// Free-form address, usually name of city
+    Address(String),
+    /// Geographical coordinates in degrees
+    Coordinates { lat: f64, lon: f64 },
+    /// Postal code with optional ISO 3166 country code
+    Zip {
+        code: String,
+        country: Option<String>,
+    },
+    /// Provider specific city ID
+    CityId(u64),
+}
+
+/// Errors for Location
+#[derive(Error, Debug)]
+pub enum LocationError {
+    #[error("Zip code should not be empty")]
+    EmptyZip,
+}
+
+impl Location {
+    /// Parse zip code in format <code>,<country> or <code>
+    pub fn parse_zip(zip: &str) -> Result<Self, LocationError> {
+        let (code, country) = match zip.rsplit_once(',') {
+            Some((code, country)) => (code.trim(), Some(country.trim().to_owned())),
+            None => (zip.trim(), None),
+        };
+
+        if code.is_empty() {
+            return Err(LocationError::EmptyZip);
+        }
+
+        Ok(Location::Zip {
+            code: code.to_owned(),
+            country,
+        })
+    }
+}
+
+/// Pretty print for Location
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Address(address) => write!(f, "{address}"),
+            Location::Coordinates { lat, lon } => write!(f, "{lat},{lon}"),
+            Location::Zip {
+                code,
+                country: Some(country),
+            } => write!(f, "{code},{country}"),
+            Location::Zip {
+                code,
+                country: None,
+            } => write!(f, "{code}"),
+            Location::CityId(id) => write!(f, "city #{id}"),
+        }
+    }
+}
+
+/// Place found by geocoding
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Place {
+    pub name: String,
+    /// State or region of the country
+    pub region: Option<String>,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Place {
+    /// Place from the full address resolved by provider (e.g. "Kyiv, Kyiv City, Ukraine"),
+    /// the first part is the name, the last one is the country and the rest is the region
+    pub fn from_address(address: &str, lat: f64, lon: f64) -> Self {
+        let mut parts = address.split(',').map(str::trim).collect::<Vec<_>>();
+        let name = parts.remove(0).to_owned();
+        let country = parts.pop().unwrap_or_default().to_owned();
+
+        Place {
+            name,
+            region: (!parts.is_empty()).then(|| parts.join(", ")),
+            country,
+            lat,
+            lon,
+        }
+    }
+
+    /// Location of the place by its coordinates
+    pub fn to_location(&self) -> Location {
+        Location::Coordinates {
+            lat: self.lat,
+            lon: self.lon,
+        }
+    }
+}
+
+/// Pretty print for Place
+impl Display for Place {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(region) = self.region.as_deref().filter(|region| !region.is_empty()) {
+            write!(f, ", {region}")?;
+        }
+        write!(f, ", {} ({}, {})", self.country, self.lat, self.lon)
+    }
+}
@@ -1,18 +1,28 @@
 #![feature(lazy_cell)]
+#![feature(is_terminal)]
 
 use clap::Parser;
 use command::Args;
 use eyre::Result;
+use std::process::ExitCode;
 
+mod air_quality;
+mod alert;
+mod astronomy;
+mod cache;
 mod command;
 mod config;
+mod date;
 mod forecast;
+mod location;
+mod output;
 mod provider;
 mod provider_loader;
+mod store;
+mod units;
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let cli = Args::parse();
-    cli.command.process()?;
 
-    Ok(())
+    cli.command.process()
 }
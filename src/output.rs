@@ -0,0 +1,736 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
+use clap::ValueEnum;
+use eyre::Result;
+use serde::Serialize;
+use std::{
+    fmt::Display,
+    io::{stdout, Write},
+};
+use thiserror::Error;
+
+use crate::{
+    air_quality::{AirQuality, AqiCategory},
+    alert::{Alert, Severity},
+    astronomy::{Astronomy, MoonPhase},
+    forecast::Forecast,
+    provider::{Agreement, ProviderType},
+    units::Units,
+};
+
+/// Output formats of the weather information
+#[derive(ValueEnum, Clone, Copy, Default, Debug)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// JSON array of reports (one per provider)
+    Json,
+    /// CSV with header and one row per forecast
+    Csv,
+    /// One JSON object per forecast per line
+    Ndjson,
+}
+
+/// Errors for Report
+#[derive(Error, Debug)]
+pub enum OutputError {
+    #[error("Failed to serialize report to JSON with error '{error}'")]
+    FailedToSerializeJson { error: serde_json::Error },
+    #[error("Failed to write report as CSV with error '{error}'")]
+    FailedToWriteCsv { error: csv::Error },
+    #[error("Failed to write report with error '{error}'")]
+    BrokenOutput { error: std::io::Error },
+}
+
+/// Weather information for the address in machine-readable form
+///
+/// JSON schema: `[{address, provider, units, date, forecasts: [Forecast], agreement?: [Agreement],
+/// air_quality?: AirQuality}]`,
+/// the array has one report per provider (the only one unless all providers are requested),
+/// where `date` is the requested (or first) date in ISO 8601, `agreement` of members
+/// on the forecasts is present only for Ensemble and `air_quality` - only if it is requested
+#[derive(Serialize)]
+pub struct Report<'a> {
+    pub address: &'a str,
+    pub provider: ProviderType,
+    pub units: Units,
+    pub date: NaiveDate,
+    pub forecasts: &'a [Forecast],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub agreement: &'a [Agreement],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub air_quality: Option<&'a AirQuality>,
+}
+
+/// Flat representation of one forecast of the report, used for CSV and NDJSON
+#[derive(Serialize)]
+struct Record<'a> {
+    address: &'a str,
+    provider: ProviderType,
+    units: Units,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+    temp: f64,
+    temp_min: Option<f64>,
+    temp_max: Option<f64>,
+    condition: &'a str,
+    feels_like: Option<f64>,
+    humidity: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_direction: Option<f64>,
+    pressure: Option<f64>,
+    clouds: Option<f64>,
+    precipitation: Option<f64>,
+    uv_index: Option<f64>,
+    temp_median: Option<f64>,
+    temp_spread: Option<f64>,
+    sunrise: Option<NaiveTime>,
+    sunset: Option<NaiveTime>,
+    aqi: Option<u32>,
+    aqi_category: Option<AqiCategory>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    o3: Option<f64>,
+    no2: Option<f64>,
+    so2: Option<f64>,
+    co: Option<f64>,
+}
+
+/// Air quality at the address in machine-readable form
+///
+/// JSON schema: `[{address, provider, air_quality: AirQuality}]`
+#[derive(Serialize)]
+pub struct AirQualityReport<'a> {
+    pub address: &'a str,
+    pub provider: ProviderType,
+    pub air_quality: &'a AirQuality,
+}
+
+/// Flat representation of the air quality report, used for CSV and NDJSON
+#[derive(Serialize)]
+struct AirQualityRecord<'a> {
+    address: &'a str,
+    provider: ProviderType,
+    aqi: Option<u32>,
+    category: Option<AqiCategory>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    o3: Option<f64>,
+    no2: Option<f64>,
+    so2: Option<f64>,
+    co: Option<f64>,
+}
+
+/// Astronomy data of the date at the address in machine-readable form
+///
+/// JSON schema: `[{address, provider, date, astronomy: Astronomy}]`,
+/// where `provider` is `null` if the data is calculated locally
+#[derive(Serialize)]
+pub struct AstronomyReport<'a> {
+    pub address: &'a str,
+    pub provider: Option<ProviderType>,
+    pub date: NaiveDate,
+    pub astronomy: &'a Astronomy,
+}
+
+/// Flat representation of the astronomy report, used for CSV and NDJSON
+#[derive(Serialize)]
+struct AstronomyRecord<'a> {
+    address: &'a str,
+    provider: Option<ProviderType>,
+    date: NaiveDate,
+    sunrise: Option<NaiveTime>,
+    sunset: Option<NaiveTime>,
+    moon_phase: Option<MoonPhase>,
+    moon_illumination: Option<f64>,
+}
+
+/// Alerts for the address in machine-readable form
+///
+/// JSON schema: `[{address, provider, alerts: [Alert]}]`,
+/// where `start` and `end` of alerts are in RFC 3339 (`null` => now and until further notice)
+#[derive(Serialize)]
+pub struct AlertReport<'a> {
+    pub address: &'a str,
+    pub provider: ProviderType,
+    pub alerts: &'a [Alert],
+}
+
+/// Flat representation of one alert of the report, used for CSV and NDJSON
+#[derive(Serialize)]
+struct AlertRecord<'a> {
+    address: &'a str,
+    provider: ProviderType,
+    event: &'a str,
+    severity: Severity,
+    start: Option<DateTime<FixedOffset>>,
+    end: Option<DateTime<FixedOffset>>,
+    description: &'a str,
+}
+
+/// Columns of AlertRecord, CSV without alerts has only them
+const ALERT_FIELDS: [&str; 7] = [
+    "address",
+    "provider",
+    "event",
+    "severity",
+    "start",
+    "end",
+    "description",
+];
+
+impl<'a> Report<'a> {
+    pub fn new(
+        address: &'a str,
+        provider: ProviderType,
+        units: Units,
+        date: NaiveDate,
+        forecasts: &'a [Forecast],
+    ) -> Self {
+        Report {
+            address,
+            provider,
+            units,
+            date,
+            forecasts,
+            agreement: &[],
+            air_quality: None,
+        }
+    }
+
+    /// Add agreement of ensemble members on the forecasts to the report
+    pub fn with_agreement(self, agreement: &'a [Agreement]) -> Self {
+        Report { agreement, ..self }
+    }
+
+    /// Add air quality to the report
+    pub fn with_air_quality(self, air_quality: Option<&'a AirQuality>) -> Self {
+        Report {
+            air_quality,
+            ..self
+        }
+    }
+
+    /// Print report to stdout in the format, text format is provided by the caller,
+    /// JSON is an array with the only report, the same as for several reports
+    pub fn print(&self, format: OutputFormat, text: impl Display) -> Result<()> {
+        print_reports(std::slice::from_ref(self), format, text)?;
+
+        Ok(())
+    }
+
+    /// Flatten forecasts, current weather gets date of the report,
+    /// air quality is repeated in each record
+    fn records(&self) -> impl Iterator<Item = Record<'_>> {
+        let air = self.air_quality;
+
+        self.forecasts.iter().map(move |forecast| {
+            let agreement = self.agreement.iter().find(|agreement| {
+                agreement.date == forecast.date && agreement.time == forecast.time
+            });
+
+            Record {
+                address: self.address,
+                provider: self.provider,
+                units: self.units,
+                date: forecast.date.unwrap_or(self.date),
+                time: forecast.time,
+                temp: forecast.temp,
+                temp_min: forecast.temp_min,
+                temp_max: forecast.temp_max,
+                condition: &forecast.condition,
+                feels_like: forecast.feels_like,
+                humidity: forecast.humidity,
+                wind_speed: forecast.wind_speed,
+                wind_direction: forecast.wind_direction,
+                pressure: forecast.pressure,
+                clouds: forecast.clouds,
+                precipitation: forecast.precipitation,
+                uv_index: forecast.uv_index,
+                temp_median: agreement.map(|agreement| agreement.temp_median),
+                temp_spread: agreement.map(|agreement| agreement.temp_spread),
+                sunrise: forecast.sunrise,
+                sunset: forecast.sunset,
+                aqi: air.and_then(|air| air.aqi),
+                aqi_category: air.and_then(|air| air.category),
+                pm2_5: air.and_then(|air| air.pm2_5),
+                pm10: air.and_then(|air| air.pm10),
+                o3: air.and_then(|air| air.o3),
+                no2: air.and_then(|air| air.no2),
+                so2: air.and_then(|air| air.so2),
+                co: air.and_then(|air| air.co),
+            }
+        })
+    }
+}
+
+/// Print several reports to stdout in the format, text format is provided by the caller,
+/// JSON is an array of reports, CSV and NDJSON have records of all reports
+pub fn print_reports(reports: &[Report], format: OutputFormat, text: impl Display) -> Result<()> {
+    write_reports(&mut stdout(), reports, format, text)
+}
+
+/// Write several reports to the output in the format, see `print_reports`
+fn write_reports(
+    output: &mut impl Write,
+    reports: &[Report],
+    format: OutputFormat,
+    text: impl Display,
+) -> Result<()> {
+    let records = reports.iter().flat_map(|report| report.records());
+
+    match format {
+        OutputFormat::Text => write_text(output, text)?,
+
+        OutputFormat::Json => write_json(output, reports)?,
+
+        OutputFormat::Ndjson => write_ndjson(output, records)?,
+
+        OutputFormat::Csv => write_csv(output, records)?,
+    }
+
+    Ok(())
+}
+
+impl<'a> AirQualityReport<'a> {
+    pub fn new(address: &'a str, provider: ProviderType, air_quality: &'a AirQuality) -> Self {
+        AirQualityReport {
+            address,
+            provider,
+            air_quality,
+        }
+    }
+
+    /// Print report to stdout in the format, text format is provided by the caller,
+    /// JSON is an array with the only report, the same as for `get`
+    pub fn print(&self, format: OutputFormat, text: impl Display) -> Result<()> {
+        self.write(&mut stdout(), format, text)
+    }
+
+    /// Write report to the output in the format, see `print`
+    fn write(
+        &self,
+        output: &mut impl Write,
+        format: OutputFormat,
+        text: impl Display,
+    ) -> Result<()> {
+        let air = self.air_quality;
+        let record = AirQualityRecord {
+            address: self.address,
+            provider: self.provider,
+            aqi: air.aqi,
+            category: air.category,
+            pm2_5: air.pm2_5,
+            pm10: air.pm10,
+            o3: air.o3,
+            no2: air.no2,
+            so2: air.so2,
+            co: air.co,
+        };
+
+        match format {
+            OutputFormat::Text => write_text(output, text)?,
+
+            OutputFormat::Json => write_json(output, std::slice::from_ref(self))?,
+
+            OutputFormat::Ndjson => write_ndjson(output, std::iter::once(record))?,
+
+            OutputFormat::Csv => write_csv(output, std::iter::once(record))?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> AstronomyReport<'a> {
+    pub fn new(
+        address: &'a str,
+        provider: Option<ProviderType>,
+        date: NaiveDate,
+        astronomy: &'a Astronomy,
+    ) -> Self {
+        AstronomyReport {
+            address,
+            provider,
+            date,
+            astronomy,
+        }
+    }
+
+    /// Print report to stdout in the format, text format is provided by the caller,
+    /// JSON is an array with the only report, the same as for `get`
+    pub fn print(&self, format: OutputFormat, text: impl Display) -> Result<()> {
+        self.write(&mut stdout(), format, text)
+    }
+
+    /// Write report to the output in the format, see `print`
+    fn write(
+        &self,
+        output: &mut impl Write,
+        format: OutputFormat,
+        text: impl Display,
+    ) -> Result<()> {
+        let astronomy = self.astronomy;
+        let record = AstronomyRecord {
+            address: self.address,
+            provider: self.provider,
+            date: self.date,
+            sunrise: astronomy.sunrise,
+            sunset: astronomy.sunset,
+            moon_phase: astronomy.moon_phase,
+            moon_illumination: astronomy.moon_illumination,
+        };
+
+        match format {
+            OutputFormat::Text => write_text(output, text)?,
+
+            OutputFormat::Json => write_json(output, std::slice::from_ref(self))?,
+
+            OutputFormat::Ndjson => write_ndjson(output, std::iter::once(record))?,
+
+            OutputFormat::Csv => write_csv(output, std::iter::once(record))?,
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> AlertReport<'a> {
+    pub fn new(address: &'a str, provider: ProviderType, alerts: &'a [Alert]) -> Self {
+        AlertReport {
+            address,
+            provider,
+            alerts,
+        }
+    }
+
+    /// Print report to stdout in the format, text format is provided by the caller,
+    /// JSON is an array with the only report, the same as for `get`
+    pub fn print(&self, format: OutputFormat, text: impl Display) -> Result<()> {
+        self.write(&mut stdout(), format, text)
+    }
+
+    /// Write report to the output in the format, see `print`
+    fn write(
+        &self,
+        output: &mut impl Write,
+        format: OutputFormat,
+        text: impl Display,
+    ) -> Result<()> {
+        let records = self.alerts.iter().map(|alert| AlertRecord {
+            address: self.address,
+            provider: self.provider,
+            event: &alert.event,
+            severity: alert.severity,
+            start: alert.start,
+            end: alert.end,
+            description: &alert.description,
+        });
+
+        match format {
+            OutputFormat::Text => write_text(output, text)?,
+
+            OutputFormat::Json => write_json(output, std::slice::from_ref(self))?,
+
+            OutputFormat::Ndjson => write_ndjson(output, records)?,
+
+            // Header is written even without alerts, so the table has the known columns
+            OutputFormat::Csv if self.alerts.is_empty() => write_csv_header(output, &ALERT_FIELDS)?,
+
+            OutputFormat::Csv => write_csv(output, records)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Write text with new line
+fn write_text(output: &mut impl Write, text: impl Display) -> Result<()> {
+    writeln!(output, "{text}").map_err(|error| OutputError::BrokenOutput { error })?;
+
+    Ok(())
+}
+
+/// Write pretty JSON array of the reports
+fn write_json(output: &mut impl Write, reports: &[impl Serialize]) -> Result<()> {
+    let json = serde_json::to_string_pretty(reports)
+        .map_err(|error| OutputError::FailedToSerializeJson { error })?;
+
+    write_text(output, json)
+}
+
+/// Write one JSON object per record per line
+fn write_ndjson(
+    output: &mut impl Write,
+    records: impl Iterator<Item = impl Serialize>,
+) -> Result<()> {
+    for record in records {
+        let json = serde_json::to_string(&record)
+            .map_err(|error| OutputError::FailedToSerializeJson { error })?;
+
+        write_text(output, json)?;
+    }
+
+    Ok(())
+}
+
+/// Write CSV with header and one row per record, the header is taken from the first record
+fn write_csv(output: &mut impl Write, records: impl Iterator<Item = impl Serialize>) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(output);
+
+    for record in records {
+        writer
+            .serialize(record)
+            .map_err(|error| OutputError::FailedToWriteCsv { error })?;
+    }
+
+    writer
+        .flush()
+        .map_err(|error| OutputError::BrokenOutput { error })?;
+
+    Ok(())
+}
+
+/// Write CSV with only the header, for reports without records
+fn write_csv_header(output: &mut impl Write, fields: &[&str]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(output);
+
+    writer
+        .write_record(fields)
+        .map_err(|error| OutputError::FailedToWriteCsv { error })?;
+    writer
+        .flush()
+        .map_err(|error| OutputError::BrokenOutput { error })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn forecasts() -> Vec<Forecast> {
+        [(9, 12.5), (10, 14.0)]
+            .into_iter()
+            .map(|(hour, temp)| Forecast {
+                date: Some(date()),
+                time: NaiveTime::from_hms_opt(hour, 0, 0),
+                temp,
+                condition: "Partly cloudy".to_owned(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn air_quality() -> AirQuality {
+        AirQuality::from_concentrations(Some(5.0), None, None, None, None, None)
+    }
+
+    fn alert() -> Alert {
+        Alert {
+            event: "Flood Warning".to_owned(),
+            severity: Severity::Severe,
+            start: None,
+            end: None,
+            description: "Rivers, overflowing".to_owned(),
+        }
+    }
+
+    /// Output of the writer as text
+    fn output(write: impl FnOnce(&mut Vec<u8>) -> Result<()>) -> String {
+        let mut output = Vec::new();
+        write(&mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn json_is_array_of_reports() {
+        let forecasts = forecasts();
+        let air_quality = air_quality();
+        let astronomy = Astronomy::default();
+        let alerts = [alert()];
+        let report = Report::new(
+            "Kyiv",
+            ProviderType::OpenMeteo,
+            Units::Metric,
+            date(),
+            &forecasts,
+        );
+
+        let outputs = [
+            output(|out| write_reports(out, &[report], OutputFormat::Json, "")),
+            output(|out| {
+                AirQualityReport::new("Kyiv", ProviderType::WeatherApi, &air_quality).write(
+                    out,
+                    OutputFormat::Json,
+                    "",
+                )
+            }),
+            output(|out| {
+                AstronomyReport::new("Kyiv", None, date(), &astronomy).write(
+                    out,
+                    OutputFormat::Json,
+                    "",
+                )
+            }),
+            output(|out| {
+                AlertReport::new("Kyiv", ProviderType::Nws, &alerts).write(
+                    out,
+                    OutputFormat::Json,
+                    "",
+                )
+            }),
+        ];
+
+        for output in outputs {
+            let json: Value = serde_json::from_str(&output).unwrap();
+
+            assert_eq!(json.as_array().map(Vec::len), Some(1), "{output}");
+            assert_eq!(json[0]["address"], "Kyiv");
+        }
+    }
+
+    #[test]
+    fn text_is_given_by_caller() {
+        let alerts = [alert()];
+        let text = output(|out| {
+            AlertReport::new("Kyiv", ProviderType::Nws, &alerts).write(
+                out,
+                OutputFormat::Text,
+                "Flood Warning",
+            )
+        });
+
+        assert_eq!(text, "Flood Warning\n");
+    }
+
+    #[test]
+    fn records_of_forecasts() {
+        let forecasts = forecasts();
+        let report = Report::new(
+            "Kyiv",
+            ProviderType::OpenMeteo,
+            Units::Metric,
+            date(),
+            &forecasts,
+        );
+
+        let csv = output(|out| write_reports(out, &[report], OutputFormat::Csv, ""));
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("address,provider,units,date,time,temp,"));
+        assert!(lines[1].starts_with("Kyiv,OpenMeteo,metric,2026-10-18,09:00:00,12.5,"));
+
+        let report = Report::new(
+            "Kyiv",
+            ProviderType::OpenMeteo,
+            Units::Metric,
+            date(),
+            &forecasts,
+        );
+        let ndjson = output(|out| write_reports(out, &[report], OutputFormat::Ndjson, ""));
+        let records = ndjson
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["time"], "10:00:00");
+        assert_eq!(records[1]["temp"], 14.0);
+        // Air quality is not requested
+        assert_eq!(records[1]["aqi"], Value::Null);
+    }
+
+    #[test]
+    fn air_quality_is_in_records_of_forecast() {
+        let forecasts = forecasts();
+        let air_quality = air_quality();
+        let report = || {
+            Report::new(
+                "Kyiv",
+                ProviderType::WeatherApi,
+                Units::Metric,
+                date(),
+                &forecasts[..1],
+            )
+            .with_air_quality(Some(&air_quality))
+        };
+
+        let csv = output(|out| write_reports(out, &[report()], OutputFormat::Csv, ""));
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let header = reader.headers().unwrap().clone();
+        let row = reader.records().next().unwrap().unwrap();
+        let column = |name| row.get(header.iter().position(|field| field == name).unwrap());
+        assert_eq!(column("aqi"), Some("28"));
+        assert_eq!(column("aqi_category"), Some("good"));
+        assert_eq!(column("pm2_5"), Some("5.0"));
+        assert_eq!(column("pm10"), Some(""));
+
+        let ndjson = output(|out| write_reports(out, &[report()], OutputFormat::Ndjson, ""));
+        let record: Value = serde_json::from_str(ndjson.trim()).unwrap();
+        assert_eq!(record["aqi"], 28);
+        assert_eq!(record["aqi_category"], "good");
+
+        let json = output(|out| write_reports(out, &[report()], OutputFormat::Json, ""));
+        let json: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json[0]["air_quality"]["aqi"], 28);
+    }
+
+    #[test]
+    fn records_of_other_reports() {
+        let air_quality = air_quality();
+        let csv = output(|out| {
+            AirQualityReport::new("Kyiv", ProviderType::WeatherApi, &air_quality).write(
+                out,
+                OutputFormat::Csv,
+                "",
+            )
+        });
+        assert_eq!(
+            csv,
+            "address,provider,aqi,category,pm2_5,pm10,o3,no2,so2,co\nKyiv,WeatherApi,28,good,5.0,,,,,\n"
+        );
+
+        let astronomy = Astronomy {
+            sunrise: NaiveTime::from_hms_opt(7, 25, 0),
+            ..Default::default()
+        };
+        let ndjson = output(|out| {
+            AstronomyReport::new("Kyiv", None, date(), &astronomy).write(
+                out,
+                OutputFormat::Ndjson,
+                "",
+            )
+        });
+        let record: Value = serde_json::from_str(ndjson.trim()).unwrap();
+        assert_eq!(record["provider"], Value::Null);
+        assert_eq!(record["sunrise"], "07:25:00");
+    }
+
+    #[test]
+    fn csv_without_alerts_has_header() {
+        let write = |alerts: &[Alert], format| {
+            output(|out| AlertReport::new("Kyiv", ProviderType::Nws, alerts).write(out, format, ""))
+        };
+
+        let csv = write(&[alert()], OutputFormat::Csv);
+        let (header, row) = csv.split_once('\n').unwrap();
+        assert_eq!(header, ALERT_FIELDS.join(","));
+        assert_eq!(
+            row,
+            "Kyiv,Nws,Flood Warning,severe,,,\"Rivers, overflowing\"\n"
+        );
+
+        assert_eq!(write(&[], OutputFormat::Csv), format!("{header}\n"));
+        assert_eq!(write(&[], OutputFormat::Ndjson), "");
+        assert_eq!(
+            write(&[], OutputFormat::Json).replace(char::is_whitespace, ""),
+            "[{\"address\":\"Kyiv\",\"provider\":\"Nws\",\"alerts\":[]}]"
+        );
+    }
+}
@@ -1,27 +1,266 @@
-use chrono::NaiveDate;
+use chrono::{FixedOffset, NaiveDate, Timelike};
 use enum_dispatch::enum_dispatch;
 use eyre::Result;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::sync::LazyLock;
+use thiserror::Error;
 
+pub use self::ensemble::Agreement;
+use self::ensemble::Ensemble;
+use self::met_norway::MetNorway;
+use self::nws::Nws;
+use self::open_meteo::OpenMeteo;
 use self::open_weather::OpenWeather;
+use self::tomorrow_io::TomorrowIo;
+use self::visual_crossing::VisualCrossing;
 use self::weather_api::WeatherApi;
-use crate::forecast::Forecast;
+use crate::{
+    air_quality::AirQuality,
+    alert::Alert,
+    astronomy::Astronomy,
+    date,
+    forecast::Forecast,
+    location::{Location, Place},
+};
 
+mod ensemble;
+mod met_norway;
+mod nws;
+mod open_meteo;
 mod open_weather;
+mod tomorrow_io;
+mod visual_crossing;
 mod weather_api;
 
-/// Trait for all weather providers
+#[cfg(test)]
+mod test_util;
+
+/// Trait for all weather providers,
+/// dates are counted in the timezone with the UTC offset `offset` (the one of the location or set by the user)
 #[enum_dispatch(Provider)]
 pub trait WeatherProvider: From<ProviderIR> {
-    fn get_weather(&self, address: &str, date: Option<&NaiveDate>) -> Result<Forecast>;
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast>;
+
+    /// Get forecast for each day of the range [from, to] with one request
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>>;
+
+    /// Get hourly forecast for the date, ordered by time
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>>;
+
+    /// Base URL of the API, responses of different servers are cached separately
+    fn base_url(&self) -> String;
+
+    /// Get weather on the past date, only some providers keep history
+    fn get_history(
+        &self,
+        _location: &Location,
+        _date: &NaiveDate,
+        _offset: FixedOffset,
+    ) -> Result<Forecast> {
+        Err(ProviderError::HistoryNotSupported.into())
+    }
+
+    /// Get the current UTC offset of the location, only some providers report timezone
+    fn get_utc_offset(&self, _location: &Location) -> Result<FixedOffset> {
+        Err(ProviderError::TimezoneNotReported.into())
+    }
+
+    /// Get severe weather alerts issued for the location, only some providers give them
+    fn get_alerts(&self, _location: &Location) -> Result<Vec<Alert>> {
+        Err(ProviderError::AlertsNotSupported.into())
+    }
+
+    /// Get current air quality at the location, only some providers measure it
+    fn get_air_quality(&self, _location: &Location) -> Result<AirQuality> {
+        Err(ProviderError::AirQualityNotSupported.into())
+    }
+
+    /// Get sunrise, sunset and moon phase at the location on the date, only some providers give them
+    fn get_astronomy(
+        &self,
+        _location: &Location,
+        _date: &NaiveDate,
+        _offset: FixedOffset,
+    ) -> Result<Astronomy> {
+        Err(ProviderError::AstronomyNotSupported.into())
+    }
+}
+
+/// Errors for optional capabilities of providers
+#[derive(Error, Debug)]
+pub enum ProviderError {
+    #[error("History of weather is not supported by the provider, use WeatherApi or OpenWeather")]
+    HistoryNotSupported,
+    #[error("Timezone of the location is not reported by the provider, set it with --tz")]
+    TimezoneNotReported,
+    #[error(
+        "Weather alerts are not supported by the provider, use WeatherApi, OpenWeather or Nws"
+    )]
+    AlertsNotSupported,
+    #[error("Air quality is not supported by the provider, use WeatherApi or OpenWeather")]
+    AirQualityNotSupported,
+    #[error("Astronomy data is not supported by the provider, use WeatherApi or OpenWeather")]
+    AstronomyNotSupported,
+}
+
+/// Trait for providers that can find places by name
+#[enum_dispatch(Provider)]
+pub trait Geocoder {
+    /// Find places matching the name, best matches first
+    fn geocode(&self, name: &str) -> Result<Vec<Place>>;
 }
 
 /// Intermediate representation of provider
 pub struct ProviderIR {
     pub provider: ProviderType,
     pub key: String,
+    /// Contact of the user for User-Agent header, empty for providers that do not require it
+    pub contact: String,
+    /// Base URL of the provider API, `None` => provider's default one
+    pub base_url: Option<String>,
+    /// Providers with their weights for pseudo-providers combining them, empty for real ones
+    pub members: Vec<(Provider, f64)>,
+}
+
+impl ProviderType {
+    /// Check that provider can not work without API key
+    pub fn requires_key(self) -> bool {
+        !matches!(
+            self,
+            ProviderType::Ensemble
+                | ProviderType::OpenMeteo
+                | ProviderType::Nws
+                | ProviderType::MetNorway
+        )
+    }
+
+    /// Check that provider identifies the user by contact in User-Agent header
+    pub fn requires_contact(self) -> bool {
+        matches!(self, ProviderType::Nws | ProviderType::MetNorway)
+    }
+
+    /// Check that provider can find places by name, so it can resolve addresses
+    pub fn can_geocode(self) -> bool {
+        !matches!(self, ProviderType::Nws | ProviderType::MetNorway)
+    }
+
+    /// Check that provider gives its own forecasts rather than combines forecasts of others
+    pub fn is_real(self) -> bool {
+        !matches!(self, ProviderType::Ensemble)
+    }
+}
+
+/// Get dates of the range [from, to] which are out of horizon
+/// (in days after today in the timezone with the UTC offset)
+pub fn dates_out_of_horizon(
+    from: &NaiveDate,
+    to: &NaiveDate,
+    horizon: RangeInclusive<i64>,
+    offset: FixedOffset,
+) -> Vec<NaiveDate> {
+    let now = date::today(offset);
+
+    from.iter_days()
+        .take_while(|date| date <= to)
+        .filter(|date| !horizon.contains(&(*date - now).num_days()))
+        .collect()
+}
+
+/// Format list of dates for messages
+pub fn format_dates(dates: &[NaiveDate]) -> String {
+    dates
+        .iter()
+        .map(|date| date.format("%d.%m.%Y").to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Convert speed from km/h to m/s rounded to hundredths
+pub fn kph_to_mps(kph: f64) -> f64 {
+    (kph / 3.6 * 100.0).round() / 100.0
+}
+
+/// Aggregate hourly forecasts of the date to Forecast for the whole day, it is used
+/// when the days of the provider are in other timezone: mean values, range of temperatures,
+/// total precipitation, the strongest wind and the condition of the hour nearest to noon
+pub fn aggregate_hours(date: &NaiveDate, hours: &[Forecast]) -> Option<Forecast> {
+    let mean = |value: fn(&Forecast) -> Option<f64>| {
+        let values = hours.iter().filter_map(value).collect::<Vec<_>>();
+
+        (!values.is_empty())
+            .then(|| (values.iter().sum::<f64>() / values.len() as f64 * 100.0).round() / 100.0)
+    };
+    let temps = hours.iter().map(|hour| hour.temp);
+    let midday = hours
+        .iter()
+        .min_by_key(|hour| hour.time.map_or(u32::MAX, |time| time.hour().abs_diff(12)))?;
+
+    Some(Forecast {
+        units: midday.units,
+        date: Some(*date),
+        time: None,
+        temp: mean(|hour| Some(hour.temp))?,
+        temp_min: temps.clone().reduce(f64::min),
+        temp_max: temps.reduce(f64::max),
+        condition: midday.condition.clone(),
+        feels_like: mean(|hour| hour.feels_like),
+        humidity: mean(|hour| hour.humidity),
+        wind_speed: hours
+            .iter()
+            .filter_map(|hour| hour.wind_speed)
+            .reduce(f64::max),
+        wind_direction: midday.wind_direction,
+        pressure: mean(|hour| hour.pressure),
+        clouds: mean(|hour| hour.clouds),
+        precipitation: hours
+            .iter()
+            .filter_map(|hour| hour.precipitation)
+            .reduce(|sum, amount| sum + amount)
+            .map(|sum| (sum * 100.0).round() / 100.0),
+        uv_index: hours
+            .iter()
+            .filter_map(|hour| hour.uv_index)
+            .reduce(f64::max),
+        sunrise: None,
+        sunset: None,
+    })
+}
+
+/// User-Agent header identifying the application and the user (contact, e.g. email)
+/// for government services, contact is skipped if it is empty
+pub fn user_agent(contact: &str) -> String {
+    let application = concat!("weather-cli/", env!("CARGO_PKG_VERSION"));
+
+    match contact.trim() {
+        "" => application.to_owned(),
+        contact => format!("{application} ({contact})"),
+    }
+}
+
+/// Error of JSON deserialization with the path to the field that failed
+pub type JsonError = serde_path_to_error::Error<serde_json::Error>;
+
+/// Deserialize JSON response tracking the path to the invalid field
+pub fn parse_json<T: DeserializeOwned>(body: &str) -> std::result::Result<T, JsonError> {
+    serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(body))
 }
 
 /// Macro for providers registration
@@ -52,6 +291,15 @@ macro_rules! register {
             map
         });
 
+        impl $provider {
+            /// Get type of the provider
+            pub fn provider_type(&self) -> $provider_type {
+                match self {
+                    $($provider::$i(_) => $provider_type::$i,)*
+                }
+            }
+        }
+
         /// Implementation of casting intermediate representation to corresponding provider
         impl From<ProviderIR> for Provider {
            fn from(value: ProviderIR) -> Self {
@@ -68,5 +316,5 @@ register!(
     Provider: Provider,
     ProviderType: ProviderType,
     ProviderTypeMap: PROVIDER_TYPE_MAP,
-    Providers: [WeatherApi, OpenWeather]
+    Providers: [WeatherApi, OpenWeather, Ensemble, OpenMeteo, TomorrowIo, VisualCrossing, Nws, MetNorway]
 );
@@ -0,0 +1,565 @@
+use chrono::{FixedOffset, NaiveDate, NaiveTime};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    mem,
+    panic::resume_unwind,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+use thiserror::Error;
+
+use super::{Geocoder, Provider, ProviderIR, WeatherProvider};
+use crate::{
+    air_quality::AirQuality,
+    alert::Alert,
+    astronomy::Astronomy,
+    forecast::Forecast,
+    location::{Location, Place},
+    units::Units,
+};
+
+/// Pseudo-provider that combines forecasts of the real providers
+pub struct Ensemble {
+    /// Providers with their weights
+    members: Vec<(Provider, f64)>,
+    /// Agreement of members on the last combined forecasts
+    agreement: Mutex<Vec<Agreement>>,
+}
+
+/// How members of the ensemble agree on the forecast for the same date and time
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Agreement {
+    /// Unit system of the values
+    #[serde(skip)]
+    pub units: Units,
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>,
+    /// Median temperature of members
+    pub temp_median: f64,
+    /// Difference between the highest and the lowest temperature of members
+    pub temp_spread: f64,
+}
+
+/// Shared category of weather conditions, providers describe the same weather differently
+/// (e.g. Light rain, Rain showers and lightrain)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum ConditionCategory {
+    Clear,
+    Clouds,
+    Fog,
+    Rain,
+    Snow,
+    Thunderstorm,
+    Unknown,
+}
+
+/// Cast from intermediate representation
+impl From<ProviderIR> for Ensemble {
+    fn from(value: ProviderIR) -> Self {
+        Ensemble {
+            members: value.members,
+            agreement: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+/// Errors for Ensemble
+#[derive(Error, Debug)]
+pub enum EnsembleError {
+    #[error("Ensemble has no providers, configure real providers or check their weights")]
+    NoMembers,
+    #[error("All providers of ensemble failed:\n{failures}")]
+    AllMembersFailed { failures: String },
+}
+
+impl WeatherProvider for Ensemble {
+    fn base_url(&self) -> String {
+        // Combined forecasts depend on the servers of all members
+        self.members
+            .iter()
+            .map(|(provider, _)| provider.base_url())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        let forecasts = self
+            .fan_out(|provider| provider.get_weather(location, date, offset))?
+            .into_iter()
+            .map(|(weight, forecast)| (weight, vec![forecast]))
+            .collect();
+
+        Ok(self.combine(forecasts).remove(0))
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        Ok(self.combine(
+            self.fan_out(|provider| provider.get_forecast_range(location, from, to, offset))?,
+        ))
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        Ok(self.combine(self.fan_out(|provider| provider.get_hourly(location, date, offset))?))
+    }
+
+    fn get_history(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        let forecasts = self
+            .fan_out(|provider| provider.get_history(location, date, offset))?
+            .into_iter()
+            .map(|(weight, forecast)| (weight, vec![forecast]))
+            .collect();
+
+        Ok(self.combine(forecasts).remove(0))
+    }
+
+    fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        // Members are at the same location, so the first one that reports timezone is enough
+        let mut error = EnsembleError::NoMembers.into();
+        for (provider, _) in &self.members {
+            match provider.get_utc_offset(location) {
+                Ok(offset) => return Ok(offset),
+                Err(member_error) => error = member_error,
+            }
+        }
+
+        Err(error)
+    }
+
+    fn get_alerts(&self, location: &Location) -> Result<Vec<Alert>> {
+        // Members share alerts of the same agencies, so each event is kept once
+        let mut alerts: Vec<Alert> = Vec::new();
+        for (_, member_alerts) in self.fan_out(|provider| provider.get_alerts(location))? {
+            for alert in member_alerts {
+                if !alerts.iter().any(|other| other.is_same(&alert)) {
+                    alerts.push(alert);
+                }
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    fn get_air_quality(&self, location: &Location) -> Result<AirQuality> {
+        let measurements = self.fan_out(|provider| provider.get_air_quality(location))?;
+
+        // Index is calculated again from weighted mean of concentrations
+        let mean = |value: fn(&AirQuality) -> Option<f64>| {
+            let (sum, weights) = measurements
+                .iter()
+                .filter_map(|(weight, air)| Some((weight * value(air)?, *weight)))
+                .fold((0.0, 0.0), |(sum, weights), (value, weight)| {
+                    (sum + value, weights + weight)
+                });
+
+            (weights > 0.0).then_some(sum / weights)
+        };
+
+        Ok(AirQuality::from_concentrations(
+            mean(|air| air.pm2_5),
+            mean(|air| air.pm10),
+            mean(|air| air.o3),
+            mean(|air| air.no2),
+            mean(|air| air.so2),
+            mean(|air| air.co),
+        ))
+    }
+
+    fn get_astronomy(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Astronomy> {
+        // The sun is the same for all members, so the first one that gives it is enough
+        let mut error = EnsembleError::NoMembers.into();
+        for (provider, _) in &self.members {
+            match provider.get_astronomy(location, date, offset) {
+                Ok(astronomy) => return Ok(astronomy),
+                Err(member_error) => error = member_error,
+            }
+        }
+
+        Err(error)
+    }
+}
+
+impl Geocoder for Ensemble {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        // Places of the first provider are enough to resolve the address
+        let mut error = EnsembleError::NoMembers.into();
+        for (provider, _) in &self.members {
+            match provider.geocode(name) {
+                Ok(places) => return Ok(places),
+                Err(member_error) => error = member_error,
+            }
+        }
+
+        Err(error)
+    }
+}
+
+impl Ensemble {
+    /// Agreement of members on the last combined forecasts, it is given only once
+    pub fn take_agreement(&self) -> Vec<Agreement> {
+        mem::take(
+            &mut *self
+                .agreement
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Combine forecasts of members, keeping their agreement on them
+    fn combine(&self, forecasts: Vec<(f64, Vec<Forecast>)>) -> Vec<Forecast> {
+        let (forecasts, agreement) = combine(forecasts).into_iter().unzip();
+        *self
+            .agreement
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = agreement;
+
+        forecasts
+    }
+
+    /// Send request to all providers concurrently,
+    /// gives results of the providers that succeeded with their weights
+    fn fan_out<T: Send>(
+        &self,
+        request: impl Fn(&Provider) -> Result<T> + Sync,
+    ) -> Result<Vec<(f64, T)>> {
+        if self.members.is_empty() {
+            return Err(EnsembleError::NoMembers.into());
+        }
+
+        let results = thread::scope(|scope| {
+            let request = &request;
+
+            let handles = self
+                .members
+                .iter()
+                .map(|(provider, weight)| {
+                    (
+                        provider.provider_type(),
+                        *weight,
+                        scope.spawn(move || request(provider)),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|(provider, weight, handle)| {
+                    let result = handle.join().unwrap_or_else(|panic| resume_unwind(panic));
+
+                    (provider, weight, result)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut successes = Vec::new();
+        let mut failures = Vec::new();
+        for (provider, weight, result) in results {
+            match result {
+                Ok(data) => successes.push((weight, data)),
+                Err(error) => failures.push(format!("{provider:?}: {error}")),
+            }
+        }
+
+        if successes.is_empty() {
+            return Err(EnsembleError::AllMembersFailed {
+                failures: failures.join("\n"),
+            }
+            .into());
+        }
+
+        // Consensus of the rest of providers is still useful
+        for failure in &failures {
+            eprintln!("Provider of ensemble failed, {failure}");
+        }
+
+        Ok(successes)
+    }
+}
+
+/// Date and time of forecast
+type Moment = (Option<NaiveDate>, Option<NaiveTime>);
+
+/// Combine forecasts of providers for the same date and time, ordered by them
+fn combine(forecasts: Vec<(f64, Vec<Forecast>)>) -> Vec<(Forecast, Agreement)> {
+    let mut moments: BTreeMap<Moment, Vec<(f64, Forecast)>> = BTreeMap::new();
+
+    for (weight, forecasts) in forecasts {
+        for forecast in forecasts {
+            moments
+                .entry((forecast.date, forecast.time))
+                .or_default()
+                .push((weight, forecast));
+        }
+    }
+
+    moments
+        .into_values()
+        .map(|moment| consensus(&moment))
+        .collect()
+}
+
+/// Weighted consensus of forecasts for the same moment:
+/// weighted mean of values, weighted majority of condition categories and spread of temperatures
+fn consensus(forecasts: &[(f64, Forecast)]) -> (Forecast, Agreement) {
+    let mean = |value: fn(&Forecast) -> Option<f64>| {
+        let (sum, weights) = forecasts
+            .iter()
+            .filter_map(|(weight, forecast)| Some((weight * value(forecast)?, *weight)))
+            .fold((0.0, 0.0), |(sum, weights), (value, weight)| {
+                (sum + value, weights + weight)
+            });
+
+        (weights > 0.0).then(|| round(sum / weights))
+    };
+
+    let mut temps = forecasts
+        .iter()
+        .map(|(_, forecast)| forecast.temp)
+        .collect::<Vec<_>>();
+    temps.sort_by(f64::total_cmp);
+    let median = match temps.len() {
+        len if len % 2 == 0 => (temps[len / 2 - 1] + temps[len / 2]) / 2.0,
+        len => temps[len / 2],
+    };
+
+    let first = &forecasts[0].1;
+
+    let forecast = Forecast {
+        units: Units::Metric,
+        date: first.date,
+        time: first.time,
+        temp: mean(|forecast| Some(forecast.temp)).unwrap_or(median),
+        temp_min: mean(|forecast| forecast.temp_min),
+        temp_max: mean(|forecast| forecast.temp_max),
+        condition: majority_condition(forecasts),
+        feels_like: mean(|forecast| forecast.feels_like),
+        humidity: mean(|forecast| forecast.humidity),
+        wind_speed: mean(|forecast| forecast.wind_speed),
+        wind_direction: circular_mean(forecasts),
+        pressure: mean(|forecast| forecast.pressure),
+        clouds: mean(|forecast| forecast.clouds),
+        precipitation: mean(|forecast| forecast.precipitation),
+        uv_index: mean(|forecast| forecast.uv_index),
+        // Providers disagree on sun only by minutes of their methods
+        sunrise: forecasts.iter().find_map(|(_, forecast)| forecast.sunrise),
+        sunset: forecasts.iter().find_map(|(_, forecast)| forecast.sunset),
+    };
+
+    let agreement = Agreement {
+        units: Units::Metric,
+        date: first.date,
+        time: first.time,
+        temp_median: round(median),
+        temp_spread: round(temps[temps.len() - 1] - temps[0]),
+    };
+
+    (forecast, agreement)
+}
+
+/// Condition of the category with the most weight, described as by its heaviest member
+/// (the first provider wins the tie)
+fn majority_condition(forecasts: &[(f64, Forecast)]) -> String {
+    let mut categories: Vec<(ConditionCategory, f64, &(f64, Forecast))> = Vec::new();
+    for member in forecasts {
+        let (weight, forecast) = member;
+        let category = ConditionCategory::of(&forecast.condition);
+
+        match categories.iter_mut().find(|(known, ..)| *known == category) {
+            Some((_, votes, heaviest)) => {
+                *votes += weight;
+                if *weight > heaviest.0 {
+                    *heaviest = member;
+                }
+            }
+            None => categories.push((category, *weight, member)),
+        }
+    }
+
+    categories
+        .iter()
+        .fold(
+            None,
+            |best: Option<&(ConditionCategory, f64, _)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            },
+        )
+        .map_or(String::new(), |(_, _, (_, forecast))| {
+            forecast.condition.clone()
+        })
+}
+
+/// Weighted mean of wind directions, 350° and 10° give 0° rather than 180°
+fn circular_mean(forecasts: &[(f64, Forecast)]) -> Option<f64> {
+    let (sin, cos, count) = forecasts
+        .iter()
+        .filter_map(|(weight, forecast)| Some((*weight, forecast.wind_direction?.to_radians())))
+        .fold((0.0, 0.0, 0), |(sin, cos, count), (weight, angle)| {
+            (
+                sin + weight * angle.sin(),
+                cos + weight * angle.cos(),
+                count + 1,
+            )
+        });
+
+    (count > 0).then(|| round(sin.atan2(cos).to_degrees().rem_euclid(360.0)))
+}
+
+impl Agreement {
+    /// Convert metric agreement to the unit system
+    pub fn into_units(self, units: Units) -> Self {
+        Agreement {
+            units,
+            temp_median: units.temperature(self.temp_median),
+            temp_spread: units.temperature_difference(self.temp_spread),
+            ..self
+        }
+    }
+}
+
+/// Pretty print for Agreement
+impl Display for Agreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let temperature = self.units.temperature_label();
+
+        writeln!(f, "Median temperature: {}{temperature}", self.temp_median)?;
+        writeln!(
+            f,
+            "Providers disagree by: {}{temperature}",
+            self.temp_spread
+        )
+    }
+}
+
+impl ConditionCategory {
+    /// Category of the condition by its keywords, the most severe weather is checked first
+    fn of(condition: &str) -> Self {
+        let condition = condition.to_lowercase();
+        let has = |keywords: &[&str]| keywords.iter().any(|keyword| condition.contains(keyword));
+
+        if has(&["thunder", "storm"]) {
+            ConditionCategory::Thunderstorm
+        } else if has(&["snow", "sleet", "flurries", "ice", "blizzard"]) {
+            ConditionCategory::Snow
+        } else if has(&["rain", "drizzle", "shower"]) {
+            ConditionCategory::Rain
+        } else if has(&["fog", "mist", "haze", "smoke"]) {
+            ConditionCategory::Fog
+        } else if has(&["cloud", "overcast"]) {
+            ConditionCategory::Clouds
+        } else if has(&["clear", "sunny", "fair"]) {
+            ConditionCategory::Clear
+        } else {
+            ConditionCategory::Unknown
+        }
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forecast(temp: f64, condition: &str) -> Forecast {
+        Forecast {
+            temp,
+            condition: condition.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn condition_categories() {
+        let cases = [
+            ("Clear sky", ConditionCategory::Clear),
+            ("Mostly sunny", ConditionCategory::Clear),
+            ("Partly cloudy", ConditionCategory::Clouds),
+            ("Overcast", ConditionCategory::Clouds),
+            ("Light fog", ConditionCategory::Fog),
+            ("Mist", ConditionCategory::Fog),
+            ("Patchy rain possible", ConditionCategory::Rain),
+            ("Light rain showers", ConditionCategory::Rain),
+            ("Freezing drizzle", ConditionCategory::Rain),
+            ("Heavy snow", ConditionCategory::Snow),
+            ("Ice pellets", ConditionCategory::Snow),
+            ("Rain and snow", ConditionCategory::Snow),
+            ("Thunderstorm with hail", ConditionCategory::Thunderstorm),
+            (
+                "Light rain showers and thunder",
+                ConditionCategory::Thunderstorm,
+            ),
+            ("Unknown", ConditionCategory::Unknown),
+        ];
+
+        for (condition, category) in cases {
+            assert_eq!(ConditionCategory::of(condition), category, "{condition}");
+        }
+    }
+
+    #[test]
+    fn majority_votes_by_category() {
+        // Rain is described differently by each provider, but together it outweighs clear sky
+        let forecasts = [
+            (1.0, forecast(10.0, "Light rain")),
+            (1.5, forecast(12.0, "Clear")),
+            (1.2, forecast(11.0, "Rain showers")),
+        ];
+
+        assert_eq!(majority_condition(&forecasts), "Rain showers");
+
+        // The first provider wins the tie
+        let forecasts = [
+            (1.0, forecast(10.0, "Clear")),
+            (1.0, forecast(12.0, "Overcast")),
+        ];
+
+        assert_eq!(majority_condition(&forecasts), "Clear");
+    }
+
+    #[test]
+    fn agreement_is_separate_from_forecast() {
+        let forecasts = [
+            (1.0, forecast(10.0, "Rain")),
+            (1.0, forecast(14.0, "Rain")),
+            (2.0, forecast(11.0, "Drizzle")),
+        ];
+
+        let (forecast, agreement) = consensus(&forecasts);
+
+        assert_eq!(forecast.temp, 11.5);
+        assert_eq!(forecast.condition, "Drizzle");
+        assert_eq!(agreement.temp_median, 11.0);
+        assert_eq!(agreement.temp_spread, 4.0);
+    }
+}
@@ -0,0 +1,216 @@
+use chrono::{FixedOffset, NaiveDate};
+use eyre::Result;
+use reqwest::{blocking::Client, header::USER_AGENT};
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+use self::response::ForecastResponse;
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, user_agent, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
+
+/// Provider for the Norwegian Meteorological Institute, it does not need API key, but needs contact of the user
+pub struct MetNorway {
+    user_agent: String,
+    base_url: String,
+    client: Client,
+}
+
+/// Default base URL of MET Norway API
+const DEFAULT_BASE_URL: &str = "https://api.met.no/weatherapi";
+
+/// Days after today covered by the forecast, it goes on for 9 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=9;
+
+/// Cast from intermediate representation
+impl From<ProviderIR> for MetNorway {
+    fn from(value: ProviderIR) -> Self {
+        MetNorway {
+            user_agent: user_agent(&value.contact),
+            base_url: value
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_BASE_URL)
+                .trim_end_matches('/')
+                .to_owned(),
+            client: Client::new(),
+        }
+    }
+}
+
+/// Errors for MetNorway
+#[derive(Error, Debug)]
+pub enum MetNorwayError {
+    #[error("Bad response with error '{error}'")]
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
+    #[error("Json is invalid with error '{error}'")]
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with reason '{reason}'")]
+    Rejected { reason: String },
+    #[error("Forecast is not found for date {date}")]
+    ForecastNotFound { date: String },
+    #[error("Forecast with provider MetNorway is not available for {date}")]
+    ForecastNotAvailable { date: String },
+    #[error("Location {location} is not supported by MetNorway, use coordinates")]
+    LocationNotSupported { location: String },
+    #[error("Geocoding is not supported by MetNorway, use coordinates")]
+    GeocodingNotSupported,
+}
+
+impl WeatherProvider for MetNorway {
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => Ok(self
+                .get_forecast_range(location, date, date, offset)?
+                .remove(0)),
+
+            // The first entry of forecast is the closest to current weather
+            None => self.fetch(location)?.current(offset),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(MetNorwayError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response = self.fetch(location)?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.daily(&date, offset))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(MetNorwayError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch(location)?.hourly(date, offset)
+    }
+}
+
+impl Geocoder for MetNorway {
+    fn geocode(&self, _name: &str) -> Result<Vec<Place>> {
+        Err(MetNorwayError::GeocodingNotSupported.into())
+    }
+}
+
+impl MetNorway {
+    /// Send request to the location forecast API and deserialize its JSON response,
+    /// one response has all forecasts for the location
+    fn fetch(&self, location: &Location) -> Result<ForecastResponse> {
+        let Location::Coordinates { lat, lon } = location else {
+            return Err(MetNorwayError::LocationNotSupported {
+                location: location.to_string(),
+            }
+            .into());
+        };
+
+        let response = self
+            .client
+            .get(format!(
+                "{base_url}/locationforecast/2.0/complete",
+                base_url = self.base_url
+            ))
+            // MET Norway rejects coordinates with more than 4 decimal places
+            .query(&[("lat", format!("{lat:.4}")), ("lon", format!("{lon:.4}"))])
+            .header(USER_AGENT, &self.user_agent)
+            .send()
+            .map_err(|error| MetNorwayError::BadResponse { error })?;
+
+        let rejected = !response.status().is_success();
+        let body = response
+            .text()
+            .map_err(|error| MetNorwayError::BadResponse { error })?;
+
+        // Rejected request has plain text reason instead of data
+        if rejected {
+            return Err(MetNorwayError::Rejected {
+                reason: body.trim().to_owned(),
+            }
+            .into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| MetNorwayError::InvalidJson { error })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        test_util::{fixture, provider_ir, serve},
+        ProviderType,
+    };
+
+    #[test]
+    fn fetch_rejects_unsuccessful_status() {
+        let (base_url, server) = serve(vec![
+            (200, fixture("met_norway/complete.json")),
+            (403, "Missing or invalid User-Agent\n".to_owned()),
+        ]);
+        let provider = MetNorway::from(provider_ir(ProviderType::MetNorway, &base_url));
+        let oslo = Location::Coordinates {
+            lat: 59.91391,
+            lon: 10.75224,
+        };
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+
+        let forecast = provider.get_weather(&oslo, None, offset).unwrap();
+        assert_eq!(forecast.temp, 11.0);
+
+        // Rejected request has plain text reason instead of JSON
+        let error = provider.get_weather(&oslo, None, offset).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<MetNorwayError>(),
+            Some(MetNorwayError::Rejected { reason }) if reason == "Missing or invalid User-Agent"
+        ));
+
+        // Coordinates are cut to 4 decimal places
+        let requests = server.join().unwrap();
+        assert_eq!(
+            requests[0],
+            "GET /locationforecast/2.0/complete?lat=59.9139&lon=10.7522 HTTP/1.1"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Timelike, Utc};
+use eyre::Result;
+use serde::Deserialize;
+
+use super::MetNorwayError;
+use crate::{date, forecast::Forecast, units::Units};
+
+/// Response of the location forecast API
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    pub properties: Properties,
+}
+
+#[derive(Deserialize)]
+pub struct Properties {
+    /// Entries are hourly for the first days and in 6 hour steps after them
+    pub timeseries: Vec<Entry>,
+}
+
+/// Weather at some moment with forecast for the next hours
+#[derive(Deserialize)]
+pub struct Entry {
+    pub time: DateTime<Utc>,
+    pub data: Data,
+}
+
+#[derive(Deserialize)]
+pub struct Data {
+    pub instant: Instant,
+    pub next_1_hours: Option<Period>,
+    pub next_6_hours: Option<Period>,
+}
+
+#[derive(Deserialize)]
+pub struct Instant {
+    pub details: InstantDetails,
+}
+
+#[derive(Deserialize)]
+pub struct InstantDetails {
+    pub air_temperature: f64,
+    pub relative_humidity: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub wind_from_direction: Option<f64>,
+    pub air_pressure_at_sea_level: Option<f64>,
+    pub cloud_area_fraction: Option<f64>,
+    pub ultraviolet_index_clear_sky: Option<f64>,
+}
+
+/// Forecast for the period after the moment
+#[derive(Deserialize)]
+pub struct Period {
+    pub summary: Summary,
+    pub details: Option<PeriodDetails>,
+}
+
+#[derive(Deserialize)]
+pub struct Summary {
+    /// Weather symbol (e.g. partlycloudy_day)
+    pub symbol_code: String,
+}
+
+#[derive(Deserialize)]
+pub struct PeriodDetails {
+    pub precipitation_amount: Option<f64>,
+}
+
+impl ForecastResponse {
+    /// Cast the first entry to Forecast
+    pub fn current(&self, offset: FixedOffset) -> Result<Forecast> {
+        Ok(self
+            .properties
+            .timeseries
+            .first()
+            .ok_or(MetNorwayError::ForecastNotFound {
+                date: date::today(offset).format("%d.%m.%Y").to_string(),
+            })?
+            .to_forecast())
+    }
+
+    /// Cast entries of the date to Forecast with values averaged for the day
+    pub fn daily(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Forecast> {
+        let entries = self.entries(date, offset)?;
+        let forecasts = entries
+            .iter()
+            .map(|entry| entry.to_forecast())
+            .collect::<Vec<_>>();
+
+        let mean = |value: fn(&Forecast) -> Option<f64>| {
+            let values = forecasts.iter().filter_map(value).collect::<Vec<_>>();
+
+            (!values.is_empty())
+                .then(|| (values.iter().sum::<f64>() / values.len() as f64 * 100.0).round() / 100.0)
+        };
+
+        // 6 hour periods starting at 0, 6, 12 and 18 o'clock UTC cover the whole day
+        let precipitation = entries
+            .iter()
+            .filter(|entry| entry.time.hour() % 6 == 0)
+            .filter_map(|entry| {
+                entry
+                    .data
+                    .next_6_hours
+                    .as_ref()?
+                    .details
+                    .as_ref()?
+                    .precipitation_amount
+            })
+            .reduce(|sum, amount| sum + amount);
+
+        // Condition of the middle of the day describes it best
+        let midday = entries
+            .iter()
+            .min_by_key(|entry| date::to_local(&entry.time, offset).hour().abs_diff(12))
+            .unwrap_or(&entries[0]);
+
+        Ok(Forecast {
+            units: Units::Metric,
+            date: Some(*date),
+            time: None,
+            temp: mean(|forecast| Some(forecast.temp)).unwrap_or_default(),
+            temp_min: None,
+            temp_max: None,
+            condition: midday.condition(),
+            feels_like: None,
+            humidity: mean(|forecast| forecast.humidity),
+            wind_speed: mean(|forecast| forecast.wind_speed),
+            wind_direction: midday.data.instant.details.wind_from_direction,
+            pressure: mean(|forecast| forecast.pressure),
+            clouds: mean(|forecast| forecast.clouds),
+            precipitation,
+            uv_index: forecasts
+                .iter()
+                .filter_map(|forecast| forecast.uv_index)
+                .reduce(f64::max),
+            sunrise: None,
+            sunset: None,
+        })
+    }
+
+    /// Cast entries of the date to Forecast (hourly or in 6 hour steps)
+    pub fn hourly(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<Forecast>> {
+        Ok(self
+            .entries(date, offset)?
+            .into_iter()
+            .map(|entry| {
+                let time = date::to_local(&entry.time, offset);
+
+                Forecast {
+                    date: Some(time.date()),
+                    time: Some(time.time()),
+                    ..entry.to_forecast()
+                }
+            })
+            .collect())
+    }
+
+    /// Entries of the date in the timezone of the location
+    fn entries(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<&Entry>> {
+        let entries = self
+            .properties
+            .timeseries
+            .iter()
+            .filter(|entry| date::to_local(&entry.time, offset).date() == *date)
+            .collect::<Vec<_>>();
+
+        if entries.is_empty() {
+            return Err(MetNorwayError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(entries)
+    }
+}
+
+impl Entry {
+    /// Cast forecast entry to Forecast
+    pub fn to_forecast(&self) -> Forecast {
+        let details = &self.data.instant.details;
+
+        Forecast {
+            units: Units::Metric,
+            date: None,
+            time: None,
+            temp: details.air_temperature,
+            temp_min: None,
+            temp_max: None,
+            condition: self.condition(),
+            // MET Norway does not give feels like temperature
+            feels_like: None,
+            humidity: details.relative_humidity,
+            wind_speed: details.wind_speed,
+            wind_direction: details.wind_from_direction,
+            pressure: details.air_pressure_at_sea_level,
+            clouds: details.cloud_area_fraction,
+            precipitation: self
+                .next_period()
+                .and_then(|period| period.details.as_ref()?.precipitation_amount),
+            uv_index: details.ultraviolet_index_clear_sky,
+            sunrise: None,
+            sunset: None,
+        }
+    }
+
+    /// Description of the weather symbol of the next period
+    fn condition(&self) -> String {
+        self.next_period().map_or("Unknown".to_owned(), |period| {
+            describe_symbol(&period.summary.symbol_code)
+        })
+    }
+
+    /// The shortest period after the moment, entries in 6 hour steps have no hourly one
+    fn next_period(&self) -> Option<&Period> {
+        self.data
+            .next_1_hours
+            .as_ref()
+            .or(self.data.next_6_hours.as_ref())
+    }
+}
+
+/// Readable description of weather symbol (e.g. lightrainshowers_day => Light rain showers)
+fn describe_symbol(symbol: &str) -> String {
+    let description = symbol
+        .split('_')
+        .next()
+        .unwrap_or(symbol)
+        .replace("clearsky", "clear sky")
+        .replace("partly", "partly ")
+        .replace("light", "light ")
+        .replace("heavy", "heavy ")
+        .replace("showers", " showers")
+        .replace("andthunder", " and thunder");
+
+    let mut chars = description.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => description,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Fixture is recorded for Oslo (UTC+02:00)
+    fn oslo() -> FixedOffset {
+        FixedOffset::east_opt(2 * 3600).unwrap()
+    }
+
+    #[test]
+    fn current() {
+        let response: ForecastResponse = parse_json(&fixture("met_norway/complete.json")).unwrap();
+        let forecast = response.current(oslo()).unwrap();
+
+        assert_eq!(forecast.temp, 11.0);
+        assert_eq!(forecast.condition, "Partly cloudy");
+        assert_eq!(forecast.feels_like, None);
+        assert_eq!(forecast.humidity, Some(70.0));
+        assert_eq!(forecast.wind_speed, Some(3.0));
+        assert_eq!(forecast.wind_direction, Some(200.0));
+        assert_eq!(forecast.pressure, Some(1012.4));
+        assert_eq!(forecast.uv_index, Some(1.3));
+        // Precipitation of the next hour
+        assert_eq!(forecast.precipitation, Some(0.0));
+    }
+
+    #[test]
+    fn daily() {
+        let response: ForecastResponse = parse_json(&fixture("met_norway/complete.json")).unwrap();
+        let forecast = response.daily(&date(18), oslo()).unwrap();
+
+        // Hourly entries of the local day are averaged
+        assert_eq!(forecast.date, Some(date(18)));
+        assert_eq!(forecast.temp, 7.5);
+        assert_eq!(forecast.humidity, Some(82.42));
+        assert_eq!(forecast.wind_speed, Some(4.0));
+        assert_eq!(forecast.uv_index, Some(1.4));
+        // 6 hour periods from 0, 6, 12 and 18 o'clock UTC are summed up
+        assert_eq!(forecast.precipitation, Some(1.5));
+        // Local noon describes the day
+        assert_eq!(forecast.condition, "Light rain showers");
+        assert_eq!(forecast.wind_direction, Some(255.0));
+
+        assert!(response.daily(&date(21), oslo()).is_err());
+    }
+
+    #[test]
+    fn hourly() {
+        let response: ForecastResponse = parse_json(&fixture("met_norway/complete.json")).unwrap();
+        let hourly = response.hourly(&date(18), oslo()).unwrap();
+
+        // Times are local
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].condition, "Partly cloudy");
+        assert_eq!(hourly[2].condition, "Clear sky");
+        assert_eq!(hourly[12].condition, "Light rain showers");
+        assert_eq!(hourly[12].precipitation, Some(0.3));
+        assert!(hourly
+            .iter()
+            .all(|forecast| forecast.date == Some(date(18))));
+
+        // Hourly entries go on only for the first days, then they are in 6 hour steps
+        let hourly = response.hourly(&date(19), oslo()).unwrap();
+        let times = hourly
+            .iter()
+            .map(|forecast| forecast.time)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            [0, 1, 2, 8, 14, 20].map(|hour| time(hour, 0)).to_vec()
+        );
+        assert_eq!(hourly[4].condition, "Cloudy");
+        assert_eq!(hourly[4].precipitation, Some(0.3));
+    }
+
+    #[test]
+    fn symbols() {
+        let cases = [
+            ("clearsky_day", "Clear sky"),
+            ("partlycloudy_night", "Partly cloudy"),
+            ("cloudy", "Cloudy"),
+            ("fog", "Fog"),
+            ("lightrainshowers_day", "Light rain showers"),
+            ("heavysnow", "Heavy snow"),
+            (
+                "rainshowersandthunder_polartwilight",
+                "Rain showers and thunder",
+            ),
+            ("heavysleetandthunder", "Heavy sleet and thunder"),
+            ("", ""),
+        ];
+
+        for (symbol, expected) in cases {
+            assert_eq!(describe_symbol(symbol), expected, "symbol {symbol}");
+        }
+    }
+
+    #[test]
+    fn changed_schema_is_reported_with_path() {
+        let body = fixture("met_norway/complete.json").replacen(
+            "\"air_temperature\": 11.0",
+            "\"air_temperature\": null",
+            1,
+        );
+        let error = parse_json::<ForecastResponse>(&body).err().unwrap();
+
+        assert_eq!(
+            error.path().to_string(),
+            "properties.timeseries[0].data.instant.details.air_temperature"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::{FixedOffset, NaiveDate};
+use eyre::Result;
+use reqwest::{blocking::Client, header::USER_AGENT};
+use serde::de::DeserializeOwned;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+use self::response::{AlertsResponse, ForecastResponse, PointResponse, ProblemResponse};
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, user_agent, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    alert::Alert,
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
+
+/// Provider for National Weather Service (US), it does not need API key, but needs contact of the user
+pub struct Nws {
+    user_agent: String,
+    base_url: String,
+    client: Client,
+}
+
+/// Default base URL of NWS API
+const DEFAULT_BASE_URL: &str = "https://api.weather.gov";
+
+/// Days after today covered by the forecast, it goes on for 6 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=6;
+
+/// Cast from intermediate representation
+impl From<ProviderIR> for Nws {
+    fn from(value: ProviderIR) -> Self {
+        Nws {
+            user_agent: user_agent(&value.contact),
+            base_url: value
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_BASE_URL)
+                .trim_end_matches('/')
+                .to_owned(),
+            client: Client::new(),
+        }
+    }
+}
+
+/// Errors for Nws
+#[derive(Error, Debug)]
+pub enum NwsError {
+    #[error("Bad response with error '{error}'")]
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
+    #[error("Json is invalid with error '{error}'")]
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with reason '{reason}'")]
+    Rejected { reason: String },
+    #[error("Forecast is not found for date {date}")]
+    ForecastNotFound { date: String },
+    #[error("Timezone is not found for location {location}")]
+    TimezoneNotFound { location: String },
+    #[error("Forecast with provider Nws is not available for {date}")]
+    ForecastNotAvailable { date: String },
+    #[error("Location {location} is not supported by Nws, use coordinates")]
+    LocationNotSupported { location: String },
+    #[error("Geocoding is not supported by Nws, use coordinates")]
+    GeocodingNotSupported,
+}
+
+impl WeatherProvider for Nws {
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => Ok(self
+                .get_forecast_range(location, date, date, offset)?
+                .remove(0)),
+
+            // NWS gives only forecasts, so the current hour is the closest to current weather
+            None => self.fetch_forecast(location, true)?.current(offset),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(NwsError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response = self.fetch_forecast(location, false)?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.daily(&date, offset))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(NwsError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch_forecast(location, true)?.hourly(date, offset)
+    }
+
+    fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        let offset = self.fetch_forecast(location, true)?.utc_offset().ok_or(
+            NwsError::TimezoneNotFound {
+                location: location.to_string(),
+            },
+        )?;
+
+        Ok(offset)
+    }
+
+    fn get_alerts(&self, location: &Location) -> Result<Vec<Alert>> {
+        let Location::Coordinates { lat, lon } = location else {
+            return Err(NwsError::LocationNotSupported {
+                location: location.to_string(),
+            }
+            .into());
+        };
+
+        Ok(self
+            .fetch::<AlertsResponse>(&format!(
+                "{base_url}/alerts/active?point={lat:.4},{lon:.4}",
+                base_url = self.base_url,
+            ))?
+            .into_alerts())
+    }
+}
+
+impl Geocoder for Nws {
+    fn geocode(&self, _name: &str) -> Result<Vec<Place>> {
+        Err(NwsError::GeocodingNotSupported.into())
+    }
+}
+
+impl Nws {
+    /// Get forecast for the grid point of the location (hourly or in 12 hour periods),
+    /// URLs of the forecasts are given by the grid point
+    fn fetch_forecast(&self, location: &Location, hourly: bool) -> Result<ForecastResponse> {
+        let Location::Coordinates { lat, lon } = location else {
+            return Err(NwsError::LocationNotSupported {
+                location: location.to_string(),
+            }
+            .into());
+        };
+
+        // NWS accepts up to 4 decimal places of coordinates
+        let point = self.fetch::<PointResponse>(&format!(
+            "{base_url}/points/{lat:.4},{lon:.4}",
+            base_url = self.base_url,
+        ))?;
+
+        let url = match hourly {
+            true => point.properties.forecast_hourly,
+            false => point.properties.forecast,
+        };
+
+        self.fetch::<ForecastResponse>(&format!("{url}?units=si"))
+    }
+
+    /// Send request to the URL and deserialize its JSON response
+    fn fetch<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(url)
+            .header(USER_AGENT, &self.user_agent)
+            .send()
+            .map_err(|error| NwsError::BadResponse { error })?;
+
+        let rejected = !response.status().is_success();
+        let body = response
+            .text()
+            .map_err(|error| NwsError::BadResponse { error })?;
+
+        // Rejected request has problem details instead of data
+        if rejected {
+            let reason = match serde_json::from_str::<ProblemResponse>(&body) {
+                Ok(problem) => problem.to_string(),
+                Err(_) => body.trim().to_owned(),
+            };
+
+            return Err(NwsError::Rejected { reason }.into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| NwsError::InvalidJson { error })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        test_util::{fixture, provider_ir, serve},
+        ProviderType,
+    };
+
+    fn washington() -> Location {
+        Location::Coordinates {
+            lat: 38.88941,
+            lon: -77.03524,
+        }
+    }
+
+    #[test]
+    fn forecast_is_fetched_by_link_of_grid_point() {
+        // Grid point links forecasts on the same server
+        let points = fixture("nws/points.json").replace("https://api.weather.gov", "{base_url}");
+        let (base_url, server) = serve(vec![
+            (200, points),
+            (200, fixture("nws/forecast_hourly.json")),
+        ]);
+        let provider = Nws::from(provider_ir(ProviderType::Nws, &base_url));
+
+        let offset = provider.get_utc_offset(&washington()).unwrap();
+        assert_eq!(offset, FixedOffset::west_opt(4 * 3600).unwrap());
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0], "GET /points/38.8894,-77.0352 HTTP/1.1");
+        assert_eq!(
+            requests[1],
+            "GET /gridpoints/LWX/97,71/forecast/hourly?units=si HTTP/1.1"
+        );
+    }
+
+    #[test]
+    fn fetch_rejects_unsuccessful_status() {
+        let (base_url, server) = serve(vec![(404, fixture("nws/error_invalid_point.json"))]);
+        let provider = Nws::from(provider_ir(ProviderType::Nws, &base_url));
+
+        let error = provider.get_utc_offset(&washington()).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<NwsError>(),
+            Some(NwsError::Rejected { reason }) if reason.starts_with("Data Unavailable For Requested Point: ")
+        ));
+
+        server.join().unwrap();
+    }
+}
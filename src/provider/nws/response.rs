@@ -0,0 +1,404 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use eyre::Result;
+use serde::Deserialize;
+use std::fmt::Display;
+
+use super::NwsError;
+use crate::{
+    alert::{Alert, Severity},
+    date,
+    forecast::Forecast,
+    units::Units,
+};
+
+/// Response of the points API, it links forecasts of the grid point
+#[derive(Deserialize)]
+pub struct PointResponse {
+    pub properties: PointProperties,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointProperties {
+    /// URL of the forecast in 12 hour periods
+    pub forecast: String,
+    /// URL of the hourly forecast
+    pub forecast_hourly: String,
+}
+
+/// Response of the gridpoint forecast API
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    pub properties: ForecastProperties,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastProperties {
+    pub periods: Vec<Period>,
+}
+
+/// Response of the active alerts API in GeoJSON format
+#[derive(Deserialize)]
+pub struct AlertsResponse {
+    pub features: Vec<AlertFeature>,
+}
+
+#[derive(Deserialize)]
+pub struct AlertFeature {
+    pub properties: AlertProperties,
+}
+
+/// Alert issued by a weather forecast office
+#[derive(Deserialize)]
+pub struct AlertProperties {
+    pub event: String,
+    /// Severity of Common Alerting Protocol (e.g. Moderate)
+    pub severity: Option<String>,
+    /// Expected start of the event
+    pub onset: Option<DateTime<FixedOffset>>,
+    /// Time the alert is in effect from, it is used if onset is not set
+    pub effective: Option<DateTime<FixedOffset>>,
+    /// Expected end of the event
+    pub ends: Option<DateTime<FixedOffset>>,
+    /// Time the alert is in effect until, it is used if end of the event is not set
+    pub expires: Option<DateTime<FixedOffset>>,
+    pub description: Option<String>,
+}
+
+/// Problem details of rejected request
+#[derive(Deserialize)]
+pub struct ProblemResponse {
+    pub title: String,
+    pub detail: Option<String>,
+}
+
+/// Forecast for an hour, or for a day or a night
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Period {
+    /// Local time of the location
+    pub start_time: DateTime<FixedOffset>,
+    pub end_time: DateTime<FixedOffset>,
+    pub is_daytime: bool,
+    pub temperature: f64,
+    /// C or F
+    pub temperature_unit: String,
+    /// Wind speed or range of speeds with unit (e.g. "10 to 15 km/h")
+    pub wind_speed: Option<String>,
+    /// Cardinal direction (e.g. NW)
+    pub wind_direction: Option<String>,
+    pub short_forecast: String,
+    pub relative_humidity: Option<Value>,
+}
+
+#[derive(Deserialize)]
+pub struct Value {
+    pub value: Option<f64>,
+}
+
+impl ForecastResponse {
+    /// Cast the hourly period of now to Forecast
+    pub fn current(&self, offset: FixedOffset) -> Result<Forecast> {
+        let now = Utc::now();
+
+        let period = self
+            .properties
+            .periods
+            .iter()
+            .find(|period| period.start_time <= now && now < period.end_time)
+            .or(self.properties.periods.first())
+            .ok_or(NwsError::ForecastNotFound {
+                date: date::today(offset).format("%d.%m.%Y").to_string(),
+            })?;
+
+        Ok(period.to_forecast())
+    }
+
+    /// UTC offset of the location, times of periods are local for it
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        Some(*self.properties.periods.first()?.start_time.offset())
+    }
+
+    /// Cast day and night periods of the date to Forecast
+    pub fn daily(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Forecast> {
+        let periods = self.periods(date, offset)?;
+
+        // Day has the highest temperature and night has the lowest one
+        let temp = periods
+            .iter()
+            .map(|period| period.to_forecast().temp)
+            .sum::<f64>()
+            / periods.len() as f64;
+        let humidities = periods
+            .iter()
+            .filter_map(|period| period.relative_humidity.as_ref()?.value)
+            .collect::<Vec<_>>();
+        let daytime = periods
+            .iter()
+            .find(|period| period.is_daytime)
+            .unwrap_or(&periods[0]);
+
+        Ok(Forecast {
+            date: Some(*date),
+            temp: (temp * 100.0).round() / 100.0,
+            humidity: (!humidities.is_empty())
+                .then(|| humidities.iter().sum::<f64>() / humidities.len() as f64),
+            wind_speed: periods
+                .iter()
+                .filter_map(|period| period.to_forecast().wind_speed)
+                .reduce(f64::max),
+            ..daytime.to_forecast()
+        })
+    }
+
+    /// Cast hourly periods of the date to Forecast
+    pub fn hourly(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<Forecast>> {
+        Ok(self
+            .periods(date, offset)?
+            .into_iter()
+            .map(|period| Forecast {
+                date: Some(*date),
+                time: Some(period.start_time.with_timezone(&offset).time()),
+                ..period.to_forecast()
+            })
+            .collect())
+    }
+
+    /// Periods that start on the date in the timezone with the UTC offset
+    fn periods(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<&Period>> {
+        let periods = self
+            .properties
+            .periods
+            .iter()
+            .filter(|period| period.start_time.with_timezone(&offset).date_naive() == *date)
+            .collect::<Vec<_>>();
+
+        if periods.is_empty() {
+            return Err(NwsError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(periods)
+    }
+}
+
+impl AlertsResponse {
+    /// Cast alerts of the response to Alert
+    pub fn into_alerts(self) -> Vec<Alert> {
+        self.features
+            .into_iter()
+            .map(|feature| {
+                let alert = feature.properties;
+
+                Alert {
+                    event: alert.event,
+                    severity: Severity::parse(alert.severity.as_deref().unwrap_or_default()),
+                    start: alert.onset.or(alert.effective),
+                    end: alert.ends.or(alert.expires),
+                    description: alert.description.unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Period {
+    /// Cast forecast period to Forecast
+    pub fn to_forecast(&self) -> Forecast {
+        let temp = match self.temperature_unit.as_str() {
+            "F" => ((self.temperature - 32.0) * 5.0 / 9.0 * 100.0).round() / 100.0,
+            _ => self.temperature,
+        };
+
+        Forecast {
+            units: Units::Metric,
+            temp,
+            condition: self.short_forecast.clone(),
+            humidity: self
+                .relative_humidity
+                .as_ref()
+                .and_then(|value| value.value),
+            wind_speed: self.wind_speed.as_deref().and_then(parse_wind_speed),
+            wind_direction: self.wind_direction.as_deref().and_then(cardinal_to_degrees),
+            // Forecast has only probability of precipitation, not its amount
+            ..Default::default()
+        }
+    }
+}
+
+impl Display for ProblemResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {detail}", self.title),
+            None => write!(f, "{}", self.title),
+        }
+    }
+}
+
+/// Parse the highest wind speed of text (e.g. "10 to 15 km/h") in m/s
+fn parse_wind_speed(text: &str) -> Option<f64> {
+    let speed = text
+        .split_whitespace()
+        .filter_map(|word| word.parse::<f64>().ok())
+        .last()?;
+
+    let speed = match text {
+        text if text.ends_with("mph") => speed * 0.44704,
+        _ => speed / 3.6,
+    };
+
+    Some((speed * 100.0).round() / 100.0)
+}
+
+/// Degrees of cardinal direction (e.g. NW => 315)
+fn cardinal_to_degrees(direction: &str) -> Option<f64> {
+    const DIRECTIONS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+
+    DIRECTIONS
+        .iter()
+        .position(|cardinal| *cardinal == direction)
+        .map(|index| index as f64 * 22.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Fixtures are recorded for Washington (UTC-04:00)
+    fn washington() -> FixedOffset {
+        FixedOffset::west_opt(4 * 3600).unwrap()
+    }
+
+    fn tokyo() -> FixedOffset {
+        FixedOffset::east_opt(9 * 3600).unwrap()
+    }
+
+    #[test]
+    fn daily() {
+        let response: ForecastResponse = parse_json(&fixture("nws/forecast.json")).unwrap();
+
+        // Washington is at UTC-04:00 in summer time
+        assert_eq!(response.utc_offset(), FixedOffset::west_opt(4 * 3600));
+
+        // Day and night periods are combined, the day describes the weather
+        let forecast = response.daily(&date(18), washington()).unwrap();
+        assert_eq!(forecast.date, Some(date(18)));
+        assert_eq!(forecast.temp, 16.0);
+        assert_eq!(forecast.condition, "Chance Showers And Thunderstorms");
+        assert_eq!(forecast.humidity, Some(79.0));
+        assert_eq!(forecast.wind_speed, Some(5.56));
+        assert_eq!(forecast.wind_direction, Some(225.0));
+
+        // Today has only the rest of the day and the night
+        let forecast = response.daily(&date(17), washington()).unwrap();
+        assert_eq!(forecast.temp, 13.0);
+        assert_eq!(forecast.condition, "Mostly Sunny");
+
+        assert!(response.daily(&date(21), washington()).is_err());
+    }
+
+    #[test]
+    fn daily_in_other_timezone() {
+        let response: ForecastResponse = parse_json(&fixture("nws/forecast.json")).unwrap();
+
+        // This afternoon, tonight and Sunday of Washington start on the 18th in Tokyo
+        let forecast = response.daily(&date(18), tokyo()).unwrap();
+        assert_eq!(forecast.temp, 15.33);
+        assert_eq!(forecast.condition, "Mostly Sunny");
+        assert!(response.daily(&date(17), tokyo()).is_err());
+    }
+
+    #[test]
+    fn hourly() {
+        let response: ForecastResponse = parse_json(&fixture("nws/forecast_hourly.json")).unwrap();
+        let hourly = response.hourly(&date(18), washington()).unwrap();
+
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].date, Some(date(18)));
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].temp, 9.0);
+        assert_eq!(hourly[0].condition, "Mostly Clear");
+        assert_eq!(hourly[0].wind_speed, Some(1.67));
+        assert_eq!(hourly[0].wind_direction, Some(180.0));
+        assert_eq!(hourly[0].humidity, Some(80.0));
+        assert_eq!(hourly[23].time, time(23, 0));
+
+        // Hours are split into days of Tokyo, 13 hours ahead of Washington
+        let hourly = response.hourly(&date(18), tokyo()).unwrap();
+        assert_eq!(hourly.len(), 21);
+        assert_eq!(hourly[0].time, time(3, 0));
+        assert_eq!(hourly[0].temp, response.properties.periods[0].temperature);
+        assert_eq!(response.hourly(&date(19), tokyo()).unwrap().len(), 13);
+    }
+
+    #[test]
+    fn fahrenheit_is_converted() {
+        let body = fixture("nws/forecast.json").replace(
+            "\"temperature\": 20,\n        \"temperatureUnit\": \"C\"",
+            "\"temperature\": 68,\n        \"temperatureUnit\": \"F\"",
+        );
+        let response: ForecastResponse = parse_json(&body).unwrap();
+        let period = &response.properties.periods[2];
+
+        assert_eq!(period.temperature_unit, "F");
+        assert_eq!(period.to_forecast().temp, 20.0);
+    }
+
+    #[test]
+    fn wind_speeds() {
+        let cases = [
+            ("10 km/h", Some(2.78)),
+            ("10 to 15 km/h", Some(4.17)),
+            ("10 to 15 mph", Some(6.71)),
+            ("5 mph", Some(2.24)),
+            ("calm", None),
+            ("", None),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(parse_wind_speed(text), expected, "wind {text}");
+        }
+    }
+
+    #[test]
+    fn cardinal_directions() {
+        let cases = [
+            ("N", Some(0.0)),
+            ("NNE", Some(22.5)),
+            ("E", Some(90.0)),
+            ("SW", Some(225.0)),
+            ("NNW", Some(337.5)),
+            ("nw", None),
+            ("VRB", None),
+            ("", None),
+        ];
+
+        for (direction, expected) in cases {
+            assert_eq!(
+                cardinal_to_degrees(direction),
+                expected,
+                "direction {direction}"
+            );
+        }
+    }
+
+    #[test]
+    fn error() {
+        let problem: ProblemResponse =
+            parse_json(&fixture("nws/error_invalid_point.json")).unwrap();
+
+        assert_eq!(
+            problem.to_string(),
+            "Data Unavailable For Requested Point: Unable to provide data for requested point 51.5074,-0.1278"
+        );
+    }
+}
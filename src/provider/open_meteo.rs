@@ -0,0 +1,376 @@
+use chrono::{FixedOffset, NaiveDate};
+use eyre::Result;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+use self::response::{ErrorResponse, ForecastResponse, SearchResponse};
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
+
+/// Provider for Open-Meteo, it does not need API key
+pub struct OpenMeteo {
+    forecast_url: String,
+    geocoding_url: String,
+    client: Client,
+}
+
+/// Default base URL of Open-Meteo forecast API
+const DEFAULT_FORECAST_URL: &str = "https://api.open-meteo.com/v1";
+
+/// Default base URL of Open-Meteo geocoding API
+const DEFAULT_GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1";
+
+/// Days after today covered by the forecast, it goes on for 15 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=15;
+
+/// Values requested for current weather and hourly forecast
+const HOURLY_VALUES: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,\
+    weather_code,wind_speed_10m,wind_direction_10m,pressure_msl,cloud_cover,precipitation";
+
+/// Values requested for daily forecast
+const DAILY_VALUES: &str = "weather_code,temperature_2m_max,temperature_2m_min,\
+    precipitation_sum,wind_speed_10m_max,wind_direction_10m_dominant,uv_index_max";
+
+/// Cast from intermediate representation
+impl From<ProviderIR> for OpenMeteo {
+    fn from(value: ProviderIR) -> Self {
+        // Custom base URL serves both APIs
+        let base_url = value
+            .base_url
+            .as_deref()
+            .map(|base_url| base_url.trim_end_matches('/').to_owned());
+
+        OpenMeteo {
+            forecast_url: base_url.clone().unwrap_or(DEFAULT_FORECAST_URL.to_owned()),
+            geocoding_url: base_url.unwrap_or(DEFAULT_GEOCODING_URL.to_owned()),
+            client: Client::new(),
+        }
+    }
+}
+
+/// Errors for OpenMeteo
+#[derive(Error, Debug)]
+pub enum OpenMeteoError {
+    #[error("Bad response with error '{error}'")]
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
+    #[error("Json is invalid with error '{error}'")]
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with reason '{reason}'")]
+    Rejected { reason: String },
+    #[error("Section {section} is not found in json")]
+    SectionNotFound { section: &'static str },
+    #[error("Forecast is not found for date {date}")]
+    ForecastNotFound { date: String },
+    #[error("Forecast with provider OpenMeteo is not available for {date}")]
+    ForecastNotAvailable { date: String },
+    #[error("Place {address} is not found")]
+    PlaceNotFound { address: String },
+    #[error("Location {location} is not supported by OpenMeteo, use address or coordinates")]
+    LocationNotSupported { location: String },
+}
+
+impl WeatherProvider for OpenMeteo {
+    fn base_url(&self) -> String {
+        format!("{},{}", self.forecast_url, self.geocoding_url)
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => Ok(self
+                .get_forecast_range(location, date, date, offset)?
+                .remove(0)),
+
+            None => self
+                .fetch::<ForecastResponse>(
+                    &self.forecast_url,
+                    "forecast",
+                    &[
+                        self.location_query(location)?.as_slice(),
+                        &[
+                            ("current", HOURLY_VALUES.to_owned()),
+                            ("wind_speed_unit", "ms".to_owned()),
+                            ("timezone", "auto".to_owned()),
+                        ],
+                    ]
+                    .concat(),
+                )?
+                .current(),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(OpenMeteoError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response = self.fetch::<ForecastResponse>(
+            &self.forecast_url,
+            "forecast",
+            &[
+                self.location_query(location)?.as_slice(),
+                &dates_query(from, to, offset),
+                &[("daily", DAILY_VALUES.to_owned())],
+            ]
+            .concat(),
+        )?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.daily(&date))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(OpenMeteoError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch::<ForecastResponse>(
+            &self.forecast_url,
+            "forecast",
+            &[
+                self.location_query(location)?.as_slice(),
+                &dates_query(date, date, offset),
+                &[("hourly", format!("{HOURLY_VALUES},uv_index"))],
+            ]
+            .concat(),
+        )?
+        .hourly(date)
+    }
+
+    fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        let offset = self
+            .fetch::<ForecastResponse>(
+                &self.forecast_url,
+                "forecast",
+                &[
+                    self.location_query(location)?.as_slice(),
+                    &[
+                        ("current", "temperature_2m".to_owned()),
+                        ("timezone", "auto".to_owned()),
+                    ],
+                ]
+                .concat(),
+            )?
+            .utc_offset_seconds
+            .and_then(FixedOffset::east_opt)
+            .ok_or(OpenMeteoError::SectionNotFound {
+                section: "utc_offset_seconds",
+            })?;
+
+        Ok(offset)
+    }
+}
+
+impl Geocoder for OpenMeteo {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        Ok(self
+            .fetch::<SearchResponse>(
+                &self.geocoding_url,
+                "search",
+                &[("name", name.to_owned()), ("count", "5".to_owned())],
+            )?
+            .results
+            .into_iter()
+            .map(Place::from)
+            .collect())
+    }
+}
+
+impl OpenMeteo {
+    /// Send request to the API endpoint and deserialize its JSON response
+    fn fetch<T: DeserializeOwned>(
+        &self,
+        base_url: &str,
+        endpoint: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let body = self
+            .client
+            .get(format!("{base_url}/{endpoint}"))
+            .query(query)
+            .send()
+            .and_then(|response| response.text())
+            .map_err(|error| OpenMeteoError::BadResponse { error })?;
+
+        // Rejected request has reason instead of data
+        if let Ok(ErrorResponse {
+            error: true,
+            reason,
+        }) = serde_json::from_str(&body)
+        {
+            return Err(OpenMeteoError::Rejected { reason }.into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| OpenMeteoError::InvalidJson { error })?)
+    }
+
+    /// Query parameters of the location, Open-Meteo works only with coordinates,
+    /// so address is resolved to the best matching place
+    fn location_query(&self, location: &Location) -> Result<Vec<(&'static str, String)>> {
+        let (lat, lon) = match location {
+            Location::Coordinates { lat, lon } => (*lat, *lon),
+            Location::Address(address) => {
+                let place = self.geocode(address)?.into_iter().next().ok_or(
+                    OpenMeteoError::PlaceNotFound {
+                        address: address.to_owned(),
+                    },
+                )?;
+
+                (place.lat, place.lon)
+            }
+            location => {
+                return Err(OpenMeteoError::LocationNotSupported {
+                    location: location.to_string(),
+                }
+                .into())
+            }
+        };
+
+        Ok(vec![
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+        ])
+    }
+}
+
+/// Query parameters of the range of dates in the timezone with the UTC offset
+fn dates_query(
+    from: &NaiveDate,
+    to: &NaiveDate,
+    offset: FixedOffset,
+) -> [(&'static str, String); 4] {
+    [
+        ("start_date", from.format("%Y-%m-%d").to_string()),
+        ("end_date", to.format("%Y-%m-%d").to_string()),
+        ("wind_speed_unit", "ms".to_owned()),
+        ("timezone", timezone(offset)),
+    ]
+}
+
+/// Name of the timezone with the UTC offset, sign of Etc/GMT zones is inverted
+/// (Etc/GMT-9 is UTC+09:00), offsets without such zone (not whole hours, e.g. +05:30)
+/// fall back to the timezone of the location
+fn timezone(offset: FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+
+    match (seconds / 3600, seconds % 3600) {
+        (0, 0) => "GMT".to_owned(),
+        (hours @ -12..=14, 0) => format!("Etc/GMT{:+}", -hours),
+        _ => "auto".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        test_util::{fixture, provider_ir, serve},
+        ProviderType,
+    };
+
+    #[test]
+    fn fetch_rejects_error_response() {
+        let (base_url, server) = serve(vec![(400, fixture("open_meteo/error_out_of_range.json"))]);
+        let provider = OpenMeteo::from(provider_ir(ProviderType::OpenMeteo, &base_url));
+        let kyiv = Location::Coordinates {
+            lat: 50.45,
+            lon: 30.52,
+        };
+
+        let error = provider
+            .get_weather(&kyiv, None, FixedOffset::east_opt(10800).unwrap())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error.downcast_ref::<OpenMeteoError>(),
+            Some(OpenMeteoError::Rejected { reason }) if reason.starts_with("Parameter 'start_date'")
+        ));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /forecast?latitude=50.45&longitude=30.52&"));
+    }
+
+    #[test]
+    fn timezones() {
+        let cases = [
+            (0, "GMT"),
+            (3 * 3600, "Etc/GMT-3"),
+            (-4 * 3600, "Etc/GMT+4"),
+            (14 * 3600, "Etc/GMT-14"),
+            (-12 * 3600, "Etc/GMT+12"),
+            (5 * 3600 + 1800, "auto"),
+            (-13 * 3600, "auto"),
+        ];
+
+        for (seconds, expected) in cases {
+            let offset = FixedOffset::east_opt(seconds).unwrap();
+            assert_eq!(timezone(offset), expected, "offset {offset}");
+        }
+    }
+
+    #[test]
+    fn dates_are_requested_in_timezone() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let (base_url, server) = serve(vec![(200, fixture("open_meteo/forecast_daily.json"))]);
+        let provider = OpenMeteo::from(provider_ir(ProviderType::OpenMeteo, &base_url));
+        let kyiv = Location::Coordinates {
+            lat: 50.45,
+            lon: 30.52,
+        };
+
+        // Days of Tokyo are requested instead of the local ones of Kyiv
+        provider
+            .fetch::<ForecastResponse>(
+                &base_url,
+                "forecast",
+                &[
+                    provider.location_query(&kyiv).unwrap().as_slice(),
+                    &dates_query(&date, &date, FixedOffset::east_opt(9 * 3600).unwrap()),
+                ]
+                .concat(),
+            )
+            .unwrap();
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains("&start_date=2026-10-18&end_date=2026-10-18&"));
+        assert!(requests[0].contains("&timezone=Etc%2FGMT-9 "));
+    }
+}
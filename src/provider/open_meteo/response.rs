@@ -0,0 +1,442 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use eyre::Result;
+use serde::{Deserialize, Deserializer};
+
+use super::OpenMeteoError;
+use crate::{forecast::Forecast, location::Place, units::Units};
+
+/// Response of the forecast API, only requested sections are present
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    /// UTC offset of the requested timezone (auto => of the location), dates and times are local for it
+    pub utc_offset_seconds: Option<i32>,
+    pub current: Option<Current>,
+    pub hourly: Option<Hourly>,
+    pub daily: Option<Daily>,
+}
+
+/// Response of the geocoding API, there are no results if nothing is found
+#[derive(Deserialize)]
+pub struct SearchResponse {
+    #[serde(default)]
+    pub results: Vec<SearchPlace>,
+}
+
+/// Error response of any API
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    pub error: bool,
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct SearchPlace {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// State or region of the country
+    pub admin1: Option<String>,
+    pub country_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Current {
+    pub temperature_2m: f64,
+    pub apparent_temperature: Option<f64>,
+    pub relative_humidity_2m: Option<f64>,
+    pub weather_code: Option<u32>,
+    pub wind_speed_10m: Option<f64>,
+    pub wind_direction_10m: Option<f64>,
+    pub pressure_msl: Option<f64>,
+    pub cloud_cover: Option<f64>,
+    pub precipitation: Option<f64>,
+}
+
+/// Hourly values, each list has one value per time
+#[derive(Deserialize)]
+pub struct Hourly {
+    #[serde(deserialize_with = "deserialize_times")]
+    pub time: Vec<NaiveDateTime>,
+    pub temperature_2m: Vec<Option<f64>>,
+    pub apparent_temperature: Vec<Option<f64>>,
+    pub relative_humidity_2m: Vec<Option<f64>>,
+    pub weather_code: Vec<Option<u32>>,
+    pub wind_speed_10m: Vec<Option<f64>>,
+    pub wind_direction_10m: Vec<Option<f64>>,
+    pub pressure_msl: Vec<Option<f64>>,
+    pub cloud_cover: Vec<Option<f64>>,
+    pub precipitation: Vec<Option<f64>>,
+    pub uv_index: Vec<Option<f64>>,
+}
+
+/// Daily aggregated values, each list has one value per date
+#[derive(Deserialize)]
+pub struct Daily {
+    pub time: Vec<NaiveDate>,
+    pub weather_code: Vec<Option<u32>>,
+    pub temperature_2m_max: Vec<Option<f64>>,
+    pub temperature_2m_min: Vec<Option<f64>>,
+    pub precipitation_sum: Vec<Option<f64>>,
+    pub wind_speed_10m_max: Vec<Option<f64>>,
+    pub wind_direction_10m_dominant: Vec<Option<f64>>,
+    pub uv_index_max: Vec<Option<f64>>,
+}
+
+impl ForecastResponse {
+    /// Cast current weather to Forecast
+    pub fn current(self) -> Result<Forecast> {
+        let current = self
+            .current
+            .ok_or(OpenMeteoError::SectionNotFound { section: "current" })?;
+
+        Ok(Forecast {
+            units: Units::Metric,
+            date: None,
+            time: None,
+            temp: current.temperature_2m,
+            temp_min: None,
+            temp_max: None,
+            condition: condition(current.weather_code),
+            feels_like: current.apparent_temperature,
+            humidity: current.relative_humidity_2m,
+            wind_speed: current.wind_speed_10m,
+            wind_direction: current.wind_direction_10m,
+            pressure: current.pressure_msl,
+            clouds: current.cloud_cover,
+            precipitation: current.precipitation,
+            // UV index is not provided for current weather
+            uv_index: None,
+            sunrise: None,
+            sunset: None,
+        })
+    }
+
+    /// Cast daily forecast for the date to Forecast
+    pub fn daily(&self, date: &NaiveDate) -> Result<Forecast> {
+        let daily = self
+            .daily
+            .as_ref()
+            .ok_or(OpenMeteoError::SectionNotFound { section: "daily" })?;
+
+        let not_found = || OpenMeteoError::ForecastNotFound {
+            date: date.format("%d.%m.%Y").to_string(),
+        };
+
+        let index = daily
+            .time
+            .iter()
+            .position(|time| time == date)
+            .ok_or_else(not_found)?;
+
+        // Daily forecast has only minimum and maximum temperature
+        let (Some(max), Some(min)) = (
+            value(&daily.temperature_2m_max, index),
+            value(&daily.temperature_2m_min, index),
+        ) else {
+            return Err(not_found().into());
+        };
+
+        Ok(Forecast {
+            date: Some(*date),
+            temp: ((max + min) / 2.0 * 100.0).round() / 100.0,
+            temp_min: Some(min),
+            temp_max: Some(max),
+            condition: condition(value(&daily.weather_code, index)),
+            wind_speed: value(&daily.wind_speed_10m_max, index),
+            wind_direction: value(&daily.wind_direction_10m_dominant, index),
+            precipitation: value(&daily.precipitation_sum, index),
+            uv_index: value(&daily.uv_index_max, index),
+            ..Default::default()
+        })
+    }
+
+    /// Cast hourly forecast for the date to Forecast
+    pub fn hourly(&self, date: &NaiveDate) -> Result<Vec<Forecast>> {
+        let hourly = self
+            .hourly
+            .as_ref()
+            .ok_or(OpenMeteoError::SectionNotFound { section: "hourly" })?;
+
+        let forecasts = hourly
+            .time
+            .iter()
+            .enumerate()
+            .filter(|(_, time)| time.date() == *date)
+            .filter_map(|(index, time)| {
+                Some(Forecast {
+                    units: Units::Metric,
+                    date: Some(time.date()),
+                    time: Some(time.time()),
+                    temp: value(&hourly.temperature_2m, index)?,
+                    temp_min: None,
+                    temp_max: None,
+                    condition: condition(value(&hourly.weather_code, index)),
+                    feels_like: value(&hourly.apparent_temperature, index),
+                    humidity: value(&hourly.relative_humidity_2m, index),
+                    wind_speed: value(&hourly.wind_speed_10m, index),
+                    wind_direction: value(&hourly.wind_direction_10m, index),
+                    pressure: value(&hourly.pressure_msl, index),
+                    clouds: value(&hourly.cloud_cover, index),
+                    precipitation: value(&hourly.precipitation, index),
+                    uv_index: value(&hourly.uv_index, index),
+                    sunrise: None,
+                    sunset: None,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if forecasts.is_empty() {
+            return Err(OpenMeteoError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(forecasts)
+    }
+}
+
+impl From<SearchPlace> for Place {
+    fn from(value: SearchPlace) -> Self {
+        Place {
+            name: value.name,
+            region: value.admin1,
+            country: value.country_code.unwrap_or_default(),
+            lat: value.latitude,
+            lon: value.longitude,
+        }
+    }
+}
+
+/// Value at the index of the list, missing and null values are none
+fn value<T: Copy>(values: &[Option<T>], index: usize) -> Option<T> {
+    values.get(index).copied().flatten()
+}
+
+/// Description of WMO weather interpretation code
+fn condition(code: Option<u32>) -> String {
+    match code {
+        Some(0) => "Clear sky",
+        Some(1) => "Mainly clear",
+        Some(2) => "Partly cloudy",
+        Some(3) => "Overcast",
+        Some(45 | 48) => "Fog",
+        Some(51 | 53 | 55) => "Drizzle",
+        Some(56 | 57) => "Freezing drizzle",
+        Some(61) => "Light rain",
+        Some(63) => "Rain",
+        Some(65) => "Heavy rain",
+        Some(66 | 67) => "Freezing rain",
+        Some(71) => "Light snow",
+        Some(73) => "Snow",
+        Some(75) => "Heavy snow",
+        Some(77) => "Snow grains",
+        Some(80..=82) => "Rain showers",
+        Some(85 | 86) => "Snow showers",
+        Some(95) => "Thunderstorm",
+        Some(96 | 99) => "Thunderstorm with hail",
+        _ => "Unknown",
+    }
+    .to_owned()
+}
+
+/// Deserialize local times in Open-Meteo format (e.g. 2023-04-02T13:00)
+fn deserialize_times<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<NaiveDateTime>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|time| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M"))
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Recorded response with the list of values cut to the length
+    fn truncated(name: &str, section: &str, values: &str, len: usize) -> ForecastResponse {
+        let mut response: Value = serde_json::from_str(&fixture(name)).unwrap();
+        let Value::Array(list) = &mut response[section][values] else {
+            panic!("{section}.{values} is not a list");
+        };
+        list.truncate(len);
+
+        parse_json(&response.to_string()).unwrap()
+    }
+
+    #[test]
+    fn current() {
+        let response: ForecastResponse = parse_json(&fixture("open_meteo/current.json")).unwrap();
+
+        // Kyiv is at UTC+03:00 in summer time
+        assert_eq!(response.utc_offset_seconds, Some(10800));
+
+        let forecast = response.current().unwrap();
+
+        assert_eq!(forecast.date, None);
+        assert_eq!(forecast.temp, 12.4);
+        assert_eq!(forecast.condition, "Overcast");
+        assert_eq!(forecast.feels_like, Some(10.9));
+        assert_eq!(forecast.humidity, Some(71.0));
+        assert_eq!(forecast.wind_speed, Some(3.4));
+        assert_eq!(forecast.wind_direction, Some(292.0));
+        assert_eq!(forecast.pressure, Some(1016.8));
+        assert_eq!(forecast.clouds, Some(100.0));
+        assert_eq!(forecast.uv_index, None);
+    }
+
+    #[test]
+    fn daily() {
+        let response: ForecastResponse =
+            parse_json(&fixture("open_meteo/forecast_daily.json")).unwrap();
+        let forecast = response.daily(&date(18)).unwrap();
+
+        assert_eq!(forecast.date, Some(date(18)));
+        // Daily forecast has only minimum and maximum, the temperature is between them
+        assert_eq!(forecast.temp, 9.65);
+        assert_eq!(forecast.temp_min, Some(7.4));
+        assert_eq!(forecast.temp_max, Some(11.9));
+        assert_eq!(forecast.condition, "Light rain");
+        assert_eq!(forecast.precipitation, Some(3.2));
+        assert_eq!(forecast.wind_speed, Some(6.3));
+        assert_eq!(forecast.wind_direction, Some(241.0));
+        assert_eq!(forecast.uv_index, Some(1.1));
+
+        // Null value is missing, the rest of the day is still there
+        let forecast = response.daily(&date(19)).unwrap();
+        assert_eq!(forecast.condition, "Rain showers");
+        assert_eq!(forecast.uv_index, None);
+
+        assert!(response.daily(&date(20)).is_err());
+    }
+
+    #[test]
+    fn daily_with_short_lists() {
+        // Day without temperature is not found rather than taken from another day
+        let response = truncated(
+            "open_meteo/forecast_daily.json",
+            "daily",
+            "temperature_2m_max",
+            2,
+        );
+        assert!(response.daily(&date(18)).is_ok());
+        assert!(response.daily(&date(19)).is_err());
+
+        // Day without the other values has only temperature
+        let response = truncated("open_meteo/forecast_daily.json", "daily", "weather_code", 1);
+        let forecast = response.daily(&date(18)).unwrap();
+        assert_eq!(forecast.condition, "Unknown");
+        assert_eq!(forecast.precipitation, Some(3.2));
+    }
+
+    #[test]
+    fn hourly() {
+        let response: ForecastResponse =
+            parse_json(&fixture("open_meteo/forecast_hourly.json")).unwrap();
+        let hourly = response.hourly(&date(17)).unwrap();
+
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].temp, 7.5);
+        assert_eq!(hourly[0].condition, "Clear sky");
+        assert_eq!(hourly[12].uv_index, Some(2.35));
+        assert_eq!(hourly[18].condition, "Fog");
+        assert_eq!(hourly[23].time, time(23, 0));
+        assert_eq!(hourly[23].condition, "Light rain");
+        assert_eq!(hourly[23].precipitation, Some(0.3));
+        assert!(hourly
+            .iter()
+            .all(|forecast| forecast.date == Some(date(17))));
+
+        assert!(response.hourly(&date(18)).is_err());
+    }
+
+    #[test]
+    fn hourly_with_short_lists() {
+        // Hours without temperature are skipped
+        let response = truncated(
+            "open_meteo/forecast_hourly.json",
+            "hourly",
+            "temperature_2m",
+            20,
+        );
+        let hourly = response.hourly(&date(17)).unwrap();
+        assert_eq!(hourly.len(), 20);
+        assert_eq!(hourly[19].time, time(19, 0));
+
+        // Hours without the other values are kept with the values missing
+        let response = truncated("open_meteo/forecast_hourly.json", "hourly", "uv_index", 10);
+        let hourly = response.hourly(&date(17)).unwrap();
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[9].uv_index, Some(1.1));
+        assert_eq!(hourly[10].uv_index, None);
+        assert_eq!(hourly[10].temp, 10.0);
+    }
+
+    #[test]
+    fn missing_section() {
+        let response: ForecastResponse = parse_json(&fixture("open_meteo/current.json")).unwrap();
+
+        assert!(response.daily(&date(17)).is_err());
+        assert!(response.hourly(&date(17)).is_err());
+    }
+
+    #[test]
+    fn conditions() {
+        let cases = [
+            (Some(0), "Clear sky"),
+            (Some(2), "Partly cloudy"),
+            (Some(48), "Fog"),
+            (Some(55), "Drizzle"),
+            (Some(57), "Freezing drizzle"),
+            (Some(65), "Heavy rain"),
+            (Some(67), "Freezing rain"),
+            (Some(77), "Snow grains"),
+            (Some(81), "Rain showers"),
+            (Some(86), "Snow showers"),
+            (Some(95), "Thunderstorm"),
+            (Some(99), "Thunderstorm with hail"),
+            (Some(4), "Unknown"),
+            (None, "Unknown"),
+        ];
+
+        for (code, expected) in cases {
+            assert_eq!(condition(code), expected, "code {code:?}");
+        }
+    }
+
+    #[test]
+    fn search() {
+        let response: SearchResponse = parse_json(&fixture("open_meteo/search.json")).unwrap();
+        let places: Vec<Place> = response.results.into_iter().map(Place::from).collect();
+
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[0].name, "Springfield");
+        assert_eq!(places[0].region.as_deref(), Some("Illinois"));
+        assert_eq!(places[0].country, "US");
+        assert_eq!(places[1].lat, 37.21533);
+
+        // Nothing found is not an error
+        let response: SearchResponse = parse_json(r#"{"generationtime_ms": 0.3}"#).unwrap();
+        assert!(response.results.is_empty());
+    }
+
+    #[test]
+    fn error() {
+        let response: ErrorResponse =
+            parse_json(&fixture("open_meteo/error_out_of_range.json")).unwrap();
+
+        assert!(response.error);
+        assert!(response
+            .reason
+            .starts_with("Parameter 'start_date' is out of allowed range"));
+    }
+}
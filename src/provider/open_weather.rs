@@ -1,157 +1,337 @@
-use chrono::{Local, NaiveDate, NaiveDateTime};
+use chrono::{FixedOffset, NaiveDate, NaiveTime};
 use eyre::Result;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use std::ops::RangeInclusive;
 use thiserror::Error;
 
-use super::{ProviderIR, WeatherProvider};
-use crate::forecast::Forecast;
+use self::response::{
+    AirPollutionResponse, CurrentResponse, ErrorResponse, ForecastResponse, GeoPlace,
+    OneCallResponse, TimemachineResponse,
+};
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    air_quality::AirQuality,
+    alert::Alert,
+    astronomy::Astronomy,
+    date,
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
 
 /// Provider for WeatherApi
 pub struct OpenWeather {
     api_key: String,
+    base_url: String,
+    client: Client,
 }
 
+/// Default base URL of OpenWeather API
+const DEFAULT_BASE_URL: &str = "https://api.openweathermap.org";
+
+/// Days after today covered by the forecast, it goes on for 5 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=5;
+
 /// Cast from intermediate representation
 impl From<ProviderIR> for OpenWeather {
     fn from(value: ProviderIR) -> Self {
-        OpenWeather { api_key: value.key }
+        OpenWeather {
+            api_key: value.key,
+            base_url: value
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_BASE_URL)
+                .trim_end_matches('/')
+                .to_owned(),
+            client: Client::new(),
+        }
     }
 }
 
-/// Wrapper for casting into Forecast
-struct OpenWeatherJson<'a> {
-    body: serde_json::Value,
-    forecast_date: Option<&'a NaiveDate>,
-}
-
 /// Errors for OpenWeather
 #[derive(Error, Debug)]
 pub enum OpenWeatherError {
     #[error("Bad response with error '{error}'")]
-    BadResponse { error: reqwest::Error },
-    #[error("Something wrong with {field} in json")]
-    InvalidJsonFormat { field: String },
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
     #[error("Json is invalid with error '{error}'")]
-    InvalidJson { error: reqwest::Error },
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with status {code} and reason '{reason}'")]
+    Rejected { code: u16, reason: String },
+    #[error("Weather condition is not found in json")]
+    ConditionNotFound,
     #[error("Forecast is not found for date {date}")]
     ForecastNotFound { date: String },
+    #[error("Timezone is not found for location {location}")]
+    TimezoneNotFound { location: String },
+    #[error("UTC offset {offset} of timezone is invalid")]
+    InvalidTimezone { offset: i32 },
     #[error("Forecast with provider OpenWeather is not available for {date}")]
     ForecastNotAvailable { date: String },
+    #[error(
+        "History with provider OpenWeather is not available for {date}, it goes back to 01.01.1979"
+    )]
+    HistoryNotAvailable { date: String },
+    #[error(
+        "{feature} with provider OpenWeather needs coordinates or address of location {location}"
+    )]
+    CoordinatesNeeded {
+        feature: &'static str,
+        location: String,
+    },
+    #[error("Air quality is not found for location {location}")]
+    AirQualityNotFound { location: String },
+    #[error(
+        "Astronomy data with provider OpenWeather is available only for today, not for {date}"
+    )]
+    AstronomyNotAvailable { date: String },
 }
 
 impl WeatherProvider for OpenWeather {
-    fn get_weather(&self, address: &str, date: Option<&NaiveDate>) -> Result<Forecast> {
-        let res: OpenWeatherJson = OpenWeatherJson {
-            body: reqwest::blocking::get(match date {
-                Some(date) => {
-                    let now = Local::now().date_naive();
-
-                    let days = (*date - now).num_days();
-
-                    //WeatherApi can get forecast up to 5 days after now
-                    if !(1..=5).contains(&days) {
-                        return Err(OpenWeatherError::ForecastNotAvailable {
-                            date: date.format("%d.%m.%Y").to_string(),
-                        }
-                        .into());
-                    }
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
 
-                    format!(
-                        // API for forecast up to 5 days
-                        "https://api.openweathermap.org/data/2.5/forecast?q={address}&appid={key}",
-                        key = self.api_key,
-                    )
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => {
+                let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+                if !unavailable.is_empty() {
+                    return Err(OpenWeatherError::ForecastNotAvailable {
+                        date: format_dates(&unavailable),
+                    }
+                    .into());
                 }
-                None => format!(
-                    // API for current weather
-                    "https://api.openweathermap.org/data/2.5/weather?q={address}&appid={key}",
-                    key = self.api_key
-                ),
-            })
-            .map_err(|error| OpenWeatherError::BadResponse { error })?
-            .json()
-            .map_err(|error| OpenWeatherError::InvalidJson { error })?,
-            forecast_date: date,
-        };
-
-        res.into_forecast()
+
+                // API for forecast up to 5 days
+                self.fetch::<ForecastResponse>("data/2.5/forecast", &location_query(location))?
+                    .forecast(date, offset)
+            }
+
+            // API for current weather
+            None => self
+                .fetch::<CurrentResponse>("data/2.5/weather", &location_query(location))?
+                .to_forecast(),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(OpenWeatherError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response =
+            self.fetch::<ForecastResponse>("data/2.5/forecast", &location_query(location))?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.forecast(&date, offset))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(OpenWeatherError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch::<ForecastResponse>("data/2.5/forecast", &location_query(location))?
+            .hourly(date, offset)
+    }
+
+    fn get_history(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        // OpenWeather keeps history since 1979
+        if *date < NaiveDate::from_ymd_opt(1979, 1, 1).unwrap_or(NaiveDate::MIN) {
+            return Err(OpenWeatherError::HistoryNotAvailable {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        let (lat, lon) = coordinates(location, "History")?;
+
+        // Weather at local noon represents the whole day
+        let noon = date.and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default());
+        let timestamp = noon.timestamp() - i64::from(offset.local_minus_utc());
+
+        self.fetch::<TimemachineResponse>(
+            "data/3.0/onecall/timemachine",
+            &[
+                ("lat", lat.to_string()),
+                ("lon", lon.to_string()),
+                ("dt", timestamp.to_string()),
+            ],
+        )?
+        .forecast(date)
+    }
+
+    fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        let offset = self
+            .fetch::<CurrentResponse>("data/2.5/weather", &location_query(location))?
+            .timezone
+            .and_then(FixedOffset::east_opt)
+            .ok_or(OpenWeatherError::TimezoneNotFound {
+                location: location.to_string(),
+            })?;
+
+        Ok(offset)
+    }
+
+    fn get_alerts(&self, location: &Location) -> Result<Vec<Alert>> {
+        let (lat, lon) = coordinates(location, "Alerts")?;
+
+        // Other sections of One Call API are not needed
+        self.fetch::<OneCallResponse>(
+            "data/3.0/onecall",
+            &[
+                ("lat", lat.to_string()),
+                ("lon", lon.to_string()),
+                ("exclude", "current,minutely,hourly,daily".to_owned()),
+            ],
+        )?
+        .into_alerts()
+    }
+
+    fn get_air_quality(&self, location: &Location) -> Result<AirQuality> {
+        let (lat, lon) = coordinates(location, "Air quality")?;
+
+        Ok(self
+            .fetch::<AirPollutionResponse>(
+                "data/2.5/air_pollution",
+                &[("lat", lat.to_string()), ("lon", lon.to_string())],
+            )?
+            .air_quality()
+            .ok_or(OpenWeatherError::AirQualityNotFound {
+                location: location.to_string(),
+            })?)
+    }
+
+    fn get_astronomy(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Astronomy> {
+        // Sunrise and sunset are given only with current weather
+        if *date != date::today(offset) {
+            return Err(OpenWeatherError::AstronomyNotAvailable {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(self
+            .fetch::<CurrentResponse>("data/2.5/weather", &location_query(location))?
+            .astronomy())
     }
 }
 
-impl<'a> OpenWeatherJson<'a> {
-    /// Parse JSON for current weather
-    pub fn into_forecast(self) -> Result<Forecast> {
-        Ok(match self.forecast_date {
-            // Parse JSON for current weather
-            None => Forecast {
-                temp: self
-                    .body
-                    .get("main")
-                    .and_then(|v| v.get("temp"))
-                    .and_then(|v| v.as_f64())
-                    .map(kelvin_to_celsius)
-                    .ok_or(OpenWeatherError::InvalidJsonFormat {
-                        field: "main:temp".to_string(),
-                    })?,
-
-                condition: self
-                    .body
-                    .get("weather")
-                    .and_then(|v| v.as_array())
-                    .and_then(|v| v.last())
-                    .and_then(|v| v.get("main"))
-                    .and_then(|v| v.as_str())
-                    .ok_or(OpenWeatherError::InvalidJsonFormat {
-                        field: "weather:main".to_string(),
-                    })?
-                    .to_owned(),
-            },
-
-            // Parse JSON for forecast
-            Some(date) => {
-                let forecast = self
-                    .body
-                    .get("list")
-                    .and_then(serde_json::Value::as_array)
-                    .and_then(|v| {
-                        v.iter().find(|v| {
-                            if let Some(v) = v
-                                .get("dt")
-                                .and_then(|v| v.as_i64())
-                                .and_then(|v| NaiveDateTime::from_timestamp_millis(v * 1000))
-                            {
-                                v.date() == *date
-                            } else {
-                                false
-                            }
-                        })
-                    })
-                    .ok_or(OpenWeatherError::ForecastNotFound {
-                        date: date.format("%d.%m.%Y").to_string(),
-                    })?;
-
-                Forecast {
-                    temp: forecast
-                        .get("main")
-                        .and_then(|v| v.get("temp"))
-                        .and_then(|v| v.as_f64())
-                        .map(kelvin_to_celsius)
-                        .ok_or(OpenWeatherError::InvalidJsonFormat {
-                            field: "main:temp".to_string(),
-                        })?,
-
-                    condition: forecast
-                        .get("weather")
-                        .and_then(|v| v.as_array())
-                        .and_then(|v| v.last())
-                        .and_then(|v| v.get("main"))
-                        .and_then(|v| v.as_str())
-                        .ok_or(OpenWeatherError::InvalidJsonFormat {
-                            field: "weather:main".to_string(),
-                        })?
-                        .to_owned(),
-                }
+impl Geocoder for OpenWeather {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        Ok(self
+            .fetch::<Vec<GeoPlace>>(
+                "geo/1.0/direct",
+                &[("q", name.to_owned()), ("limit", "5".to_owned())],
+            )?
+            .into_iter()
+            .map(Place::from)
+            .collect())
+    }
+}
+
+impl OpenWeather {
+    /// Send request to the API endpoint and deserialize its JSON response
+    fn fetch<T: DeserializeOwned>(&self, endpoint: &str, query: &[(&str, String)]) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{base_url}/{endpoint}", base_url = self.base_url))
+            .query(query)
+            .query(&[("appid", &self.api_key)])
+            .send()
+            .map_err(|error| OpenWeatherError::BadResponse { error })?;
+        let status = response.status();
+        let body = response
+            .text()
+            .map_err(|error| OpenWeatherError::BadResponse { error })?;
+
+        // Rejected request has code and message instead of data,
+        // successful forecast has "cod" too, so only the status tells them apart
+        if !status.is_success() {
+            return Err(OpenWeatherError::Rejected {
+                code: status.as_u16(),
+                reason: serde_json::from_str::<ErrorResponse>(&body)
+                    .map_or(body, |error| error.message),
             }
-        })
+            .into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| OpenWeatherError::InvalidJson { error })?)
+    }
+}
+
+/// Query parameters of the location for OpenWeather API
+fn location_query(location: &Location) -> Vec<(&'static str, String)> {
+    match location {
+        Location::Address(address) => vec![("q", address.to_owned())],
+        Location::Coordinates { lat, lon } => {
+            vec![("lat", lat.to_string()), ("lon", lon.to_string())]
+        }
+        Location::Zip {
+            code,
+            country: Some(country),
+        } => vec![("zip", format!("{code},{country}"))],
+        Location::Zip {
+            code,
+            country: None,
+        } => vec![("zip", code.to_owned())],
+        Location::CityId(id) => vec![("id", id.to_string())],
+    }
+}
+
+/// Coordinates of the location, APIs of the feature (e.g. One Call 3.0) accept only them
+fn coordinates(location: &Location, feature: &'static str) -> Result<(f64, f64)> {
+    match location {
+        Location::Coordinates { lat, lon } => Ok((*lat, *lon)),
+        location => Err(OpenWeatherError::CoordinatesNeeded {
+            feature,
+            location: location.to_string(),
+        }
+        .into()),
     }
 }
 
@@ -0,0 +1,607 @@
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use eyre::Result;
+use serde::Deserialize;
+
+use super::{kelvin_to_celsius, OpenWeatherError};
+use crate::{
+    air_quality::AirQuality,
+    alert::{Alert, Severity},
+    astronomy::Astronomy,
+    date,
+    forecast::Forecast,
+    location::Place,
+    units::Units,
+};
+
+/// Response of the current weather API,
+/// it has the same format as items of the forecast list
+pub type CurrentResponse = Entry;
+
+/// Response of the 5 day forecast API
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    pub list: Vec<Entry>,
+}
+
+/// Response of the timemachine API for the past moment
+#[derive(Deserialize)]
+pub struct TimemachineResponse {
+    pub data: Vec<Moment>,
+}
+
+/// Response of the One Call API with alerts only
+#[derive(Deserialize)]
+pub struct OneCallResponse {
+    /// UTC offset of the location in seconds
+    pub timezone_offset: i32,
+    /// Present only if there are alerts
+    #[serde(default)]
+    pub alerts: Vec<OneCallAlert>,
+}
+
+/// Alert issued by the national weather agency
+#[derive(Deserialize)]
+pub struct OneCallAlert {
+    pub event: String,
+    /// Unix timestamp in UTC
+    pub start: i64,
+    /// Unix timestamp in UTC
+    pub end: i64,
+    pub description: String,
+}
+
+/// Response of the air pollution API with the current measurement
+#[derive(Deserialize)]
+pub struct AirPollutionResponse {
+    pub list: Vec<AirPollution>,
+}
+
+/// Air pollution at some moment
+#[derive(Deserialize)]
+pub struct AirPollution {
+    pub components: Components,
+}
+
+/// Concentrations of pollutants in µg/m³
+#[derive(Deserialize)]
+pub struct Components {
+    pub co: Option<f64>,
+    pub no2: Option<f64>,
+    pub o3: Option<f64>,
+    pub so2: Option<f64>,
+    pub pm2_5: Option<f64>,
+    pub pm10: Option<f64>,
+}
+
+/// Response of any API if the request is rejected (e.g. invalid API key)
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    pub message: String,
+}
+
+/// Item of the response of the direct geocoding API
+#[derive(Deserialize)]
+pub struct GeoPlace {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub country: String,
+    pub state: Option<String>,
+}
+
+/// Weather at some moment
+#[derive(Deserialize)]
+pub struct Entry {
+    /// Unix timestamp in UTC
+    pub dt: i64,
+    /// UTC offset of the location in seconds, present only in current weather
+    pub timezone: Option<i32>,
+    pub main: Main,
+    pub weather: Vec<Condition>,
+    pub wind: Option<Wind>,
+    pub clouds: Option<Clouds>,
+    /// Present only if it is raining
+    pub rain: Option<Precipitation>,
+    /// Present only if it is snowing
+    pub snow: Option<Precipitation>,
+    pub sys: Option<Sys>,
+}
+
+/// Weather at the past moment, it has flat format unlike entries of the forecast
+#[derive(Deserialize)]
+pub struct Moment {
+    /// Temperature in Kelvin
+    pub temp: f64,
+    /// Feels like temperature in Kelvin
+    pub feels_like: Option<f64>,
+    pub humidity: Option<f64>,
+    pub pressure: Option<f64>,
+    pub clouds: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub wind_deg: Option<f64>,
+    pub weather: Vec<Condition>,
+    pub rain: Option<Precipitation>,
+    pub snow: Option<Precipitation>,
+    pub uvi: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct Main {
+    /// Temperature in Kelvin
+    pub temp: f64,
+    /// Feels like temperature in Kelvin
+    pub feels_like: Option<f64>,
+    pub humidity: Option<f64>,
+    pub pressure: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct Condition {
+    pub main: String,
+}
+
+/// Sun of the day, present only in current weather
+#[derive(Deserialize)]
+pub struct Sys {
+    /// Unix timestamp in UTC
+    pub sunrise: Option<i64>,
+    /// Unix timestamp in UTC
+    pub sunset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct Wind {
+    pub speed: f64,
+    pub deg: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct Clouds {
+    pub all: f64,
+}
+
+/// Precipitation volume in mm, for the last hour (current weather)
+/// or for 3 hours (forecast)
+#[derive(Deserialize)]
+pub struct Precipitation {
+    #[serde(rename = "1h")]
+    pub one_hour: Option<f64>,
+    #[serde(rename = "3h")]
+    pub three_hours: Option<f64>,
+}
+
+impl Precipitation {
+    fn volume(&self) -> f64 {
+        self.one_hour.or(self.three_hours).unwrap_or_default()
+    }
+}
+
+impl ForecastResponse {
+    /// Aggregate forecast entries for the date (in 3 hour steps) to Forecast for the whole day:
+    /// mean values, range of temperatures, total precipitation, the strongest wind
+    /// and the most frequent condition
+    pub fn forecast(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Forecast> {
+        let entries = self
+            .list
+            .iter()
+            .filter(|entry| local_time(entry, offset).map_or(false, |time| time.date() == *date))
+            .collect::<Vec<_>>();
+
+        if entries.is_empty() {
+            return Err(OpenWeatherError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        let mean = |value: fn(&Entry) -> Option<f64>| {
+            let values = entries
+                .iter()
+                .filter_map(|entry| value(entry))
+                .collect::<Vec<_>>();
+
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let temps = entries.iter().map(|entry| entry.main.temp);
+
+        let mut conditions: Vec<(&str, usize)> = Vec::new();
+        for entry in &entries {
+            let condition = &entry
+                .weather
+                .last()
+                .ok_or(OpenWeatherError::ConditionNotFound)?
+                .main;
+
+            match conditions.iter_mut().find(|(known, _)| known == condition) {
+                Some((_, count)) => *count += 1,
+                None => conditions.push((condition, 1)),
+            }
+        }
+        // The earliest condition wins the tie
+        let condition = conditions
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map_or(String::new(), |(condition, _)| (*condition).to_owned());
+
+        let wind = entries
+            .iter()
+            .filter_map(|entry| entry.wind.as_ref())
+            .max_by(|left, right| left.speed.total_cmp(&right.speed));
+
+        let precipitation = entries
+            .iter()
+            .filter_map(|entry| precipitation(&entry.rain, &entry.snow))
+            .reduce(|total, volume| total + volume);
+
+        Ok(Forecast {
+            units: Units::Metric,
+            date: Some(*date),
+            time: None,
+            temp: kelvin_to_celsius(mean(|entry| Some(entry.main.temp)).unwrap_or_default()),
+            temp_min: temps.clone().reduce(f64::min).map(kelvin_to_celsius),
+            temp_max: temps.reduce(f64::max).map(kelvin_to_celsius),
+            condition,
+            feels_like: mean(|entry| entry.main.feels_like).map(kelvin_to_celsius),
+            humidity: mean(|entry| entry.main.humidity).map(round),
+            wind_speed: wind.map(|wind| wind.speed),
+            wind_direction: wind.and_then(|wind| wind.deg),
+            pressure: mean(|entry| entry.main.pressure).map(round),
+            clouds: mean(|entry| entry.clouds.as_ref().map(|clouds| clouds.all)).map(round),
+            precipitation: precipitation.map(round),
+            // UV index is not provided by these endpoints
+            uv_index: None,
+            // Sun is given only with current weather
+            sunrise: None,
+            sunset: None,
+        })
+    }
+
+    /// Cast all forecast entries for the date to Forecast (in 3 hour steps)
+    pub fn hourly(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<Forecast>> {
+        let forecasts = self
+            .list
+            .iter()
+            .filter_map(|entry| {
+                local_time(entry, offset)
+                    .filter(|time| time.date() == *date)
+                    .map(|time| (time, entry))
+            })
+            .map(|(time, entry)| {
+                Ok(Forecast {
+                    date: Some(time.date()),
+                    time: Some(time.time()),
+                    ..entry.to_forecast()?
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if forecasts.is_empty() {
+            return Err(OpenWeatherError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(forecasts)
+    }
+}
+
+/// Time of the entry in the timezone with the UTC offset
+fn local_time(entry: &Entry, offset: FixedOffset) -> Option<NaiveDateTime> {
+    Some(date::to_local(
+        &Utc.timestamp_opt(entry.dt, 0).single()?,
+        offset,
+    ))
+}
+
+impl TimemachineResponse {
+    /// Cast weather at the past moment to Forecast for the date
+    pub fn forecast(&self, date: &NaiveDate) -> Result<Forecast> {
+        let moment = self
+            .data
+            .first()
+            .ok_or(OpenWeatherError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            })?;
+
+        Ok(Forecast {
+            units: Units::Metric,
+            date: Some(*date),
+            time: None,
+            temp: kelvin_to_celsius(moment.temp),
+            temp_min: None,
+            temp_max: None,
+            condition: moment
+                .weather
+                .last()
+                .ok_or(OpenWeatherError::ConditionNotFound)?
+                .main
+                .clone(),
+            feels_like: moment.feels_like.map(kelvin_to_celsius),
+            humidity: moment.humidity,
+            wind_speed: moment.wind_speed,
+            wind_direction: moment.wind_deg,
+            pressure: moment.pressure,
+            clouds: moment.clouds,
+            precipitation: precipitation(&moment.rain, &moment.snow),
+            uv_index: moment.uvi,
+            sunrise: None,
+            sunset: None,
+        })
+    }
+}
+
+impl OneCallResponse {
+    /// Cast alerts of the response to Alert with times in the timezone of the location
+    pub fn into_alerts(self) -> Result<Vec<Alert>> {
+        let offset = FixedOffset::east_opt(self.timezone_offset).ok_or(
+            OpenWeatherError::InvalidTimezone {
+                offset: self.timezone_offset,
+            },
+        )?;
+
+        Ok(self
+            .alerts
+            .into_iter()
+            .map(|alert| Alert {
+                event: alert.event,
+                // OpenWeather does not rate alerts
+                severity: Severity::Unknown,
+                start: offset.timestamp_opt(alert.start, 0).single(),
+                end: offset.timestamp_opt(alert.end, 0).single(),
+                description: alert.description,
+            })
+            .collect())
+    }
+}
+
+impl AirPollutionResponse {
+    /// Cast the current measurement to AirQuality
+    pub fn air_quality(&self) -> Option<AirQuality> {
+        let components = &self.list.first()?.components;
+
+        // Index of OpenWeather (1-5) has its own scale, the common one is calculated instead
+        Some(AirQuality::from_concentrations(
+            components.pm2_5,
+            components.pm10,
+            components.o3,
+            components.no2,
+            components.so2,
+            components.co,
+        ))
+    }
+}
+
+impl Entry {
+    /// Cast weather entry to Forecast
+    pub fn to_forecast(&self) -> Result<Forecast> {
+        Ok(Forecast {
+            units: Units::Metric,
+            date: None,
+            time: None,
+            temp: kelvin_to_celsius(self.main.temp),
+            temp_min: None,
+            temp_max: None,
+            condition: self
+                .weather
+                .last()
+                .ok_or(OpenWeatherError::ConditionNotFound)?
+                .main
+                .clone(),
+            feels_like: self.main.feels_like.map(kelvin_to_celsius),
+            humidity: self.main.humidity,
+            wind_speed: self.wind.as_ref().map(|wind| wind.speed),
+            wind_direction: self.wind.as_ref().and_then(|wind| wind.deg),
+            pressure: self.main.pressure,
+            clouds: self.clouds.as_ref().map(|clouds| clouds.all),
+            precipitation: precipitation(&self.rain, &self.snow),
+            // UV index is not provided by these endpoints
+            uv_index: None,
+            sunrise: self.sun_time(|sys| sys.sunrise),
+            sunset: self.sun_time(|sys| sys.sunset),
+        })
+    }
+
+    /// Cast sunrise and sunset of current weather to Astronomy
+    pub fn astronomy(&self) -> Astronomy {
+        Astronomy {
+            sunrise: self.sun_time(|sys| sys.sunrise),
+            sunset: self.sun_time(|sys| sys.sunset),
+            ..Default::default()
+        }
+    }
+
+    /// Local time of the sun event, known only for current weather
+    fn sun_time(&self, event: fn(&Sys) -> Option<i64>) -> Option<NaiveTime> {
+        let offset = FixedOffset::east_opt(self.timezone?)?;
+
+        Some(
+            offset
+                .timestamp_opt(event(self.sys.as_ref()?)?, 0)
+                .single()?
+                .time(),
+        )
+    }
+}
+
+impl From<GeoPlace> for Place {
+    fn from(value: GeoPlace) -> Self {
+        Place {
+            name: value.name,
+            region: value.state,
+            country: value.country,
+            lat: value.lat,
+            lon: value.lon,
+        }
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Total volume of rain and snow, none if there is no precipitation
+fn precipitation(rain: &Option<Precipitation>, snow: &Option<Precipitation>) -> Option<f64> {
+    match (rain, snow) {
+        (None, None) => None,
+        (rain, snow) => Some(
+            rain.as_ref().map_or(0.0, Precipitation::volume)
+                + snow.as_ref().map_or(0.0, Precipitation::volume),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Fixtures are recorded for Tokyo (UTC+09:00)
+    fn tokyo() -> FixedOffset {
+        FixedOffset::east_opt(9 * 3600).unwrap()
+    }
+
+    #[test]
+    fn current() {
+        let response: CurrentResponse = parse_json(&fixture("open_weather/current.json")).unwrap();
+        let forecast = response.to_forecast().unwrap();
+
+        assert_eq!(forecast.temp, 16.37);
+        assert_eq!(forecast.condition, "Rain");
+        assert_eq!(forecast.feels_like, Some(16.21));
+        assert_eq!(forecast.humidity, Some(84.0));
+        assert_eq!(forecast.wind_speed, Some(3.6));
+        assert_eq!(forecast.wind_direction, Some(40.0));
+        assert_eq!(forecast.clouds, Some(75.0));
+        assert_eq!(forecast.precipitation, Some(0.35));
+        assert_eq!(forecast.sunrise, time(5, 49));
+        assert_eq!(forecast.sunset, time(17, 4));
+        assert_eq!(response.timezone, Some(32400));
+    }
+
+    #[test]
+    fn forecast() {
+        let response: ForecastResponse =
+            parse_json(&fixture("open_weather/forecast.json")).unwrap();
+        let forecast = response.forecast(&date(18), tokyo()).unwrap();
+
+        // The whole day is aggregated, not taken from its first entry
+        assert_eq!(forecast.date, Some(date(18)));
+        assert_eq!(forecast.temp, 15.52);
+        assert_eq!(forecast.temp_min, Some(12.75));
+        assert_eq!(forecast.temp_max, Some(19.15));
+        assert_eq!(forecast.condition, "Clear");
+        assert_eq!(forecast.precipitation, Some(2.8));
+        assert_eq!(forecast.humidity, Some(70.0));
+        assert_eq!(forecast.clouds, Some(40.0));
+        assert_eq!(forecast.wind_speed, Some(2.5));
+
+        // The rest of today has no precipitation at all
+        let today = response.forecast(&date(17), tokyo()).unwrap();
+        assert_eq!(today.temp_min, Some(14.75));
+        assert_eq!(today.precipitation, None);
+
+        assert!(response.forecast(&date(21), tokyo()).is_err());
+    }
+
+    #[test]
+    fn hourly() {
+        let response: ForecastResponse =
+            parse_json(&fixture("open_weather/forecast.json")).unwrap();
+        let hourly = response.hourly(&date(18), tokyo()).unwrap();
+
+        // Entries are in 3 hour steps in local time of the location
+        assert_eq!(hourly.len(), 8);
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[7].time, time(21, 0));
+        assert!(hourly
+            .iter()
+            .all(|forecast| forecast.date == Some(date(18))));
+
+        // Dates are counted in the timezone set by the user, not in the one of the city
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let hourly = response.hourly(&date(18), utc).unwrap();
+        assert_eq!(hourly.len(), 6);
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[5].time, time(15, 0));
+        assert_eq!(
+            response.forecast(&date(17), utc).unwrap().temp_min,
+            Some(12.75)
+        );
+    }
+
+    #[test]
+    fn timemachine() {
+        let response: TimemachineResponse =
+            parse_json(&fixture("open_weather/timemachine.json")).unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 9, 3).unwrap();
+        let forecast = response.forecast(&day).unwrap();
+
+        assert_eq!(forecast.date, Some(day));
+        assert_eq!(forecast.temp, 30.0);
+        assert_eq!(forecast.condition, "Clouds");
+        assert_eq!(forecast.uv_index, Some(8.1));
+        assert_eq!(forecast.precipitation, None);
+    }
+
+    #[test]
+    fn alerts() {
+        let response: OneCallResponse =
+            parse_json(&fixture("open_weather/onecall_alerts.json")).unwrap();
+        let alerts = response.into_alerts().unwrap();
+        let offset = FixedOffset::east_opt(9 * 3600).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event, "Heavy Rain Warning");
+        assert_eq!(alerts[0].severity, Severity::Unknown);
+        assert_eq!(
+            alerts[0].start,
+            offset.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).single()
+        );
+    }
+
+    #[test]
+    fn air_pollution() {
+        let response: AirPollutionResponse =
+            parse_json(&fixture("open_weather/air_pollution.json")).unwrap();
+        let air_quality = response.air_quality().unwrap();
+
+        assert_eq!(air_quality.pm2_5, Some(40.5));
+        assert_eq!(air_quality.co, Some(201.94));
+        assert!(air_quality.aqi.is_some());
+    }
+
+    #[test]
+    fn geocoding() {
+        let places: Vec<GeoPlace> = parse_json(&fixture("open_weather/geo_direct.json")).unwrap();
+        let places: Vec<Place> = places.into_iter().map(Place::from).collect();
+
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[0].name, "Springfield");
+        assert_eq!(places[0].region.as_deref(), Some("Illinois"));
+        assert_eq!(places[1].lat, 37.2081729);
+    }
+
+    #[test]
+    fn error() {
+        let response: ErrorResponse =
+            parse_json(&fixture("open_weather/error_unauthorized.json")).unwrap();
+
+        assert!(response.message.starts_with("Invalid API key"));
+    }
+
+    #[test]
+    fn changed_schema_is_reported_with_path() {
+        let body =
+            fixture("open_weather/current.json").replace("\"temp\": 289.52", "\"temp\": \"warm\"");
+        let error = parse_json::<CurrentResponse>(&body).err().unwrap();
+
+        assert_eq!(error.path().to_string(), "main.temp");
+    }
+}
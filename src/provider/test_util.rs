@@ -0,0 +1,77 @@
+use chrono::{NaiveDate, NaiveTime};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    thread::{self, JoinHandle},
+};
+
+use super::{ProviderIR, ProviderType};
+
+/// Recorded response of the API by its path in tests/fixtures (e.g. "open_weather/current.json")
+pub fn fixture(path: &str) -> String {
+    let path = format!("{}/tests/fixtures/{path}", env!("CARGO_MANIFEST_DIR"));
+
+    std::fs::read_to_string(&path).unwrap_or_else(|error| panic!("{path}: {error}"))
+}
+
+/// Date in October 2026, when the fixtures were recorded
+pub fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
+
+pub fn time(hour: u32, min: u32) -> Option<NaiveTime> {
+    NaiveTime::from_hms_opt(hour, min, 0)
+}
+
+/// Provider with the API key and contact using the server as its base URL
+pub fn provider_ir(provider: ProviderType, base_url: &str) -> ProviderIR {
+    ProviderIR {
+        provider,
+        key: "key".to_owned(),
+        contact: "me@example.com".to_owned(),
+        base_url: Some(base_url.to_owned()),
+        members: Vec::new(),
+    }
+}
+
+/// Serve the responses (status and body) to the requests one by one on a local port,
+/// gives the base URL of the server and the thread that returns the request lines it got,
+/// `{base_url}` in the bodies is replaced with the base URL for links to the server itself
+pub fn serve(responses: Vec<(u16, String)>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let responses = responses
+        .into_iter()
+        .map(|(status, body)| (status, body.replace("{base_url}", &base_url)))
+        .collect::<Vec<_>>();
+
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut lines = BufReader::new(stream.try_clone().unwrap()).lines();
+            requests.push(lines.next().unwrap().unwrap());
+
+            // Requests have no body, so they end with the empty line after headers
+            for line in lines {
+                if line.unwrap().is_empty() {
+                    break;
+                }
+            }
+
+            // Connection is closed after the response, so the next request opens a new one
+            write!(
+                stream,
+                "HTTP/1.1 {status} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+
+        requests
+    });
+
+    (base_url, handle)
+}
@@ -0,0 +1,215 @@
+use chrono::{FixedOffset, NaiveDate};
+use eyre::Result;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+use self::response::{ErrorResponse, ForecastResponse, RealtimeResponse};
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
+
+/// Provider for Tomorrow.io
+pub struct TomorrowIo {
+    api_key: String,
+    base_url: String,
+    client: Client,
+}
+
+/// Default base URL of Tomorrow.io API
+const DEFAULT_BASE_URL: &str = "https://api.tomorrow.io/v4";
+
+/// Days after today covered by the daily forecast, it goes on for 5 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=5;
+
+/// Days after today covered by the hourly forecast, it goes on only for 120 hours after now
+const HOURLY_HORIZON: RangeInclusive<i64> = 0..=4;
+
+/// Cast from intermediate representation
+impl From<ProviderIR> for TomorrowIo {
+    fn from(value: ProviderIR) -> Self {
+        TomorrowIo {
+            api_key: value.key,
+            base_url: value
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_BASE_URL)
+                .trim_end_matches('/')
+                .to_owned(),
+            client: Client::new(),
+        }
+    }
+}
+
+/// Errors for TomorrowIo
+#[derive(Error, Debug)]
+pub enum TomorrowIoError {
+    #[error("Bad response with error '{error}'")]
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
+    #[error("Json is invalid with error '{error}'")]
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with reason '{reason}'")]
+    Rejected { reason: String },
+    #[error("Forecast is not found for date {date}")]
+    ForecastNotFound { date: String },
+    #[error("Forecast with provider TomorrowIo is not available for {date}")]
+    ForecastNotAvailable { date: String },
+    #[error("Location {location} is not supported by TomorrowIo, use address, coordinates or postal code")]
+    LocationNotSupported { location: String },
+}
+
+impl WeatherProvider for TomorrowIo {
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => Ok(self
+                .get_forecast_range(location, date, date, offset)?
+                .remove(0)),
+
+            // API for realtime weather
+            None => Ok(self
+                .fetch::<RealtimeResponse>("weather/realtime", &location_query(location)?)?
+                .to_forecast()),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(TomorrowIoError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response = self.fetch::<ForecastResponse>(
+            "weather/forecast",
+            &[
+                location_query(location)?.as_slice(),
+                &[("timesteps", "1d".to_owned())],
+            ]
+            .concat(),
+        )?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.daily(&date, offset))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, HOURLY_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(TomorrowIoError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch::<ForecastResponse>(
+            "weather/forecast",
+            &[
+                location_query(location)?.as_slice(),
+                &[("timesteps", "1h".to_owned())],
+            ]
+            .concat(),
+        )?
+        .hourly(date, offset)
+    }
+}
+
+impl Geocoder for TomorrowIo {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        // Tomorrow.io has no geocoding API, but it resolves the address of realtime weather
+        let location = self
+            .fetch::<RealtimeResponse>(
+                "weather/realtime",
+                &location_query(&Location::Address(name.to_owned()))?,
+            )?
+            .location;
+
+        Ok(vec![Place::from_address(
+            location.name.as_deref().unwrap_or(name),
+            location.lat,
+            location.lon,
+        )])
+    }
+}
+
+impl TomorrowIo {
+    /// Send request to the API endpoint and deserialize its JSON response
+    fn fetch<T: DeserializeOwned>(&self, endpoint: &str, query: &[(&str, String)]) -> Result<T> {
+        let body = self
+            .client
+            .get(format!("{base_url}/{endpoint}", base_url = self.base_url))
+            .query(query)
+            .query(&[("units", "metric"), ("apikey", &self.api_key)])
+            .send()
+            .and_then(|response| response.text())
+            .map_err(|error| TomorrowIoError::BadResponse { error })?;
+
+        // Rejected request has error code and message instead of data
+        if let Ok(ErrorResponse { code, message }) = serde_json::from_str(&body) {
+            return Err(TomorrowIoError::Rejected {
+                reason: format!("{code}: {message}"),
+            }
+            .into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| TomorrowIoError::InvalidJson { error })?)
+    }
+}
+
+/// Query parameter `location` of the location for Tomorrow.io API
+fn location_query(location: &Location) -> Result<[(&'static str, String); 1]> {
+    let location = match location {
+        Location::Address(address) => address.to_owned(),
+        Location::Coordinates { lat, lon } => format!("{lat},{lon}"),
+        Location::Zip {
+            code,
+            country: Some(country),
+        } => format!("{code} {country}"),
+        Location::Zip {
+            code,
+            country: None,
+        } => code.to_owned(),
+        Location::CityId(_) => {
+            return Err(TomorrowIoError::LocationNotSupported {
+                location: location.to_string(),
+            }
+            .into())
+        }
+    };
+
+    Ok([("location", location)])
+}
@@ -0,0 +1,324 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use eyre::Result;
+use serde::Deserialize;
+
+use super::TomorrowIoError;
+use crate::{date, forecast::Forecast, units::Units};
+
+/// Response of the realtime weather API
+#[derive(Deserialize)]
+pub struct RealtimeResponse {
+    pub data: Moment,
+    pub location: ResolvedLocation,
+}
+
+/// Response of the forecast API, only requested timelines are present
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    pub timelines: Timelines,
+}
+
+/// Error response of any API
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    pub code: u32,
+    pub message: String,
+}
+
+/// Location the request was resolved to
+#[derive(Deserialize)]
+pub struct ResolvedLocation {
+    pub lat: f64,
+    pub lon: f64,
+    /// Full address, present only if the location was set by address
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Timelines {
+    #[serde(default)]
+    pub hourly: Vec<Moment>,
+    #[serde(default)]
+    pub daily: Vec<Day>,
+}
+
+/// Weather at some moment
+#[derive(Deserialize)]
+pub struct Moment {
+    pub time: DateTime<Utc>,
+    pub values: Values,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Values {
+    pub temperature: f64,
+    pub temperature_apparent: Option<f64>,
+    pub humidity: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub wind_direction: Option<f64>,
+    pub pressure_sea_level: Option<f64>,
+    pub cloud_cover: Option<f64>,
+    /// Intensity in mm/hr
+    pub rain_intensity: Option<f64>,
+    /// Intensity in mm/hr
+    pub snow_intensity: Option<f64>,
+    pub uv_index: Option<f64>,
+    pub weather_code: Option<u32>,
+}
+
+/// Aggregated weather for the whole day
+#[derive(Deserialize)]
+pub struct Day {
+    /// Start of the day (6 AM of the location) in UTC
+    pub time: DateTime<Utc>,
+    pub values: DayValues,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayValues {
+    pub temperature_avg: f64,
+    pub temperature_apparent_avg: Option<f64>,
+    pub humidity_avg: Option<f64>,
+    pub wind_speed_avg: Option<f64>,
+    pub wind_direction_avg: Option<f64>,
+    pub pressure_sea_level_avg: Option<f64>,
+    pub cloud_cover_avg: Option<f64>,
+    pub rain_accumulation_sum: Option<f64>,
+    pub snow_accumulation_sum: Option<f64>,
+    pub uv_index_max: Option<f64>,
+    pub weather_code_max: Option<u32>,
+}
+
+impl RealtimeResponse {
+    /// Cast realtime weather to Forecast
+    pub fn to_forecast(&self) -> Forecast {
+        self.data.values.to_forecast()
+    }
+}
+
+impl ForecastResponse {
+    /// Cast daily forecast for the date to Forecast
+    pub fn daily(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Forecast> {
+        let values = &self
+            .timelines
+            .daily
+            .iter()
+            .find(|day| date::to_local(&day.time, offset).date() == *date)
+            .ok_or(TomorrowIoError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            })?
+            .values;
+
+        Ok(Forecast {
+            units: Units::Metric,
+            date: Some(*date),
+            time: None,
+            temp: values.temperature_avg,
+            temp_min: None,
+            temp_max: None,
+            condition: condition(values.weather_code_max),
+            feels_like: values.temperature_apparent_avg,
+            humidity: values.humidity_avg,
+            wind_speed: values.wind_speed_avg,
+            wind_direction: values.wind_direction_avg,
+            pressure: values.pressure_sea_level_avg,
+            clouds: values.cloud_cover_avg,
+            precipitation: sum(values.rain_accumulation_sum, values.snow_accumulation_sum),
+            uv_index: values.uv_index_max,
+            sunrise: None,
+            sunset: None,
+        })
+    }
+
+    /// Cast hourly forecast for the date to Forecast
+    pub fn hourly(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<Forecast>> {
+        let forecasts = self
+            .timelines
+            .hourly
+            .iter()
+            .map(|moment| (date::to_local(&moment.time, offset), moment))
+            .filter(|(time, _)| time.date() == *date)
+            .map(|(time, moment)| Forecast {
+                date: Some(time.date()),
+                time: Some(time.time()),
+                ..moment.values.to_forecast()
+            })
+            .collect::<Vec<_>>();
+
+        if forecasts.is_empty() {
+            return Err(TomorrowIoError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(forecasts)
+    }
+}
+
+impl Values {
+    /// Cast weather values to Forecast
+    pub fn to_forecast(&self) -> Forecast {
+        Forecast {
+            units: Units::Metric,
+            date: None,
+            time: None,
+            temp: self.temperature,
+            temp_min: None,
+            temp_max: None,
+            condition: condition(self.weather_code),
+            feels_like: self.temperature_apparent,
+            humidity: self.humidity,
+            wind_speed: self.wind_speed,
+            wind_direction: self.wind_direction,
+            pressure: self.pressure_sea_level,
+            clouds: self.cloud_cover,
+            precipitation: sum(self.rain_intensity, self.snow_intensity),
+            uv_index: self.uv_index,
+            sunrise: None,
+            sunset: None,
+        }
+    }
+}
+
+/// Sum of rain and snow, none if both are missing
+fn sum(rain: Option<f64>, snow: Option<f64>) -> Option<f64> {
+    match (rain, snow) {
+        (None, None) => None,
+        (rain, snow) => Some(rain.unwrap_or_default() + snow.unwrap_or_default()),
+    }
+}
+
+/// Description of Tomorrow.io weather code
+fn condition(code: Option<u32>) -> String {
+    match code {
+        Some(1000) => "Clear",
+        Some(1100) => "Mostly clear",
+        Some(1101) => "Partly cloudy",
+        Some(1102) => "Mostly cloudy",
+        Some(1001) => "Cloudy",
+        Some(2000) => "Fog",
+        Some(2100) => "Light fog",
+        Some(4000) => "Drizzle",
+        Some(4001) => "Rain",
+        Some(4200) => "Light rain",
+        Some(4201) => "Heavy rain",
+        Some(5000) => "Snow",
+        Some(5001) => "Flurries",
+        Some(5100) => "Light snow",
+        Some(5101) => "Heavy snow",
+        Some(6000) => "Freezing drizzle",
+        Some(6001) => "Freezing rain",
+        Some(6200) => "Light freezing rain",
+        Some(6201) => "Heavy freezing rain",
+        Some(7000) => "Ice pellets",
+        Some(7101) => "Heavy ice pellets",
+        Some(7102) => "Light ice pellets",
+        Some(8000) => "Thunderstorm",
+        _ => "Unknown",
+    }
+    .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Fixtures are recorded for London (UTC+01:00)
+    fn london() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    #[test]
+    fn realtime() {
+        let response: RealtimeResponse = parse_json(&fixture("tomorrow_io/realtime.json")).unwrap();
+
+        assert_eq!(
+            response.location.name.as_deref(),
+            Some("London, Greater London, England, United Kingdom")
+        );
+
+        let forecast = response.to_forecast();
+
+        assert_eq!(forecast.temp, 11.8);
+        assert_eq!(forecast.condition, "Light rain");
+        assert_eq!(forecast.humidity, Some(83.0));
+        assert_eq!(forecast.wind_speed, Some(4.6));
+        assert_eq!(forecast.pressure, Some(1011.2));
+        assert_eq!(forecast.precipitation, Some(0.4));
+    }
+
+    #[test]
+    fn daily() {
+        let response: ForecastResponse =
+            parse_json(&fixture("tomorrow_io/forecast_daily.json")).unwrap();
+        let forecast = response.daily(&date(17), london()).unwrap();
+
+        assert_eq!(forecast.date, Some(date(17)));
+        assert_eq!(forecast.temp, 12.4);
+        assert_eq!(forecast.condition, "Light rain");
+        assert_eq!(forecast.feels_like, Some(11.9));
+        assert_eq!(forecast.precipitation, Some(3.1));
+        assert_eq!(forecast.uv_index, Some(2.0));
+
+        // Snow is missing, so precipitation is rain only
+        assert_eq!(
+            response.daily(&date(19), london()).unwrap().precipitation,
+            Some(0.0)
+        );
+        assert!(response.daily(&date(20), london()).is_err());
+    }
+
+    #[test]
+    fn hourly() {
+        let response: ForecastResponse =
+            parse_json(&fixture("tomorrow_io/forecast_hourly.json")).unwrap();
+        let hourly = response.hourly(&date(18), london()).unwrap();
+
+        // Hours are local, so the day starts at 23:00 UTC of the previous one
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].date, Some(date(18)));
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].condition, "Drizzle");
+        assert_eq!(hourly[0].precipitation, Some(0.2));
+        assert_eq!(hourly[3].condition, "Partly cloudy");
+    }
+
+    #[test]
+    fn missing_timeline() {
+        let daily: ForecastResponse =
+            parse_json(&fixture("tomorrow_io/forecast_daily.json")).unwrap();
+        let hourly: ForecastResponse =
+            parse_json(&fixture("tomorrow_io/forecast_hourly.json")).unwrap();
+
+        assert!(daily.hourly(&date(18), london()).is_err());
+        assert!(hourly.daily(&date(18), london()).is_err());
+    }
+
+    #[test]
+    fn error() {
+        let response: ErrorResponse =
+            parse_json(&fixture("tomorrow_io/error_too_many_calls.json")).unwrap();
+
+        assert_eq!(response.code, 429001);
+        assert!(response.message.starts_with("The request limit"));
+
+        // Successful responses must not be taken for errors
+        assert!(parse_json::<ErrorResponse>(&fixture("tomorrow_io/realtime.json")).is_err());
+        assert!(parse_json::<ErrorResponse>(&fixture("tomorrow_io/forecast_daily.json")).is_err());
+    }
+
+    #[test]
+    fn missing_section_is_reported_with_path() {
+        let body = fixture("tomorrow_io/realtime.json").replace("\"values\"", "\"measurements\"");
+        let error = parse_json::<RealtimeResponse>(&body).err().unwrap();
+
+        assert_eq!(error.path().to_string(), "data");
+    }
+}
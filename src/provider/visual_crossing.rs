@@ -0,0 +1,330 @@
+use chrono::{FixedOffset, NaiveDate};
+use eyre::Result;
+use reqwest::blocking::Client;
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+use self::response::TimelineResponse;
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
+
+/// Provider for Visual Crossing
+pub struct VisualCrossing {
+    api_key: String,
+    base_url: String,
+    client: Client,
+}
+
+/// Default base URL of Visual Crossing API
+const DEFAULT_BASE_URL: &str =
+    "https://weather.visualcrossing.com/VisualCrossingWebServices/rest/services";
+
+/// Days after today covered by the forecast, it goes on for 14 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=14;
+
+/// Cast from intermediate representation
+impl From<ProviderIR> for VisualCrossing {
+    fn from(value: ProviderIR) -> Self {
+        VisualCrossing {
+            api_key: value.key,
+            base_url: value
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_BASE_URL)
+                .trim_end_matches('/')
+                .to_owned(),
+            client: Client::new(),
+        }
+    }
+}
+
+/// Errors for VisualCrossing
+#[derive(Error, Debug)]
+pub enum VisualCrossingError {
+    #[error("Bad response with error '{error}'")]
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
+    #[error("Json is invalid with error '{error}'")]
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with reason '{reason}'")]
+    Rejected { reason: String },
+    #[error("Current weather is not found in json")]
+    CurrentNotFound,
+    #[error("Forecast is not found for date {date}")]
+    ForecastNotFound { date: String },
+    #[error("Timezone is not found for location {location}")]
+    TimezoneNotFound { location: String },
+    #[error("Forecast with provider VisualCrossing is not available for {date}")]
+    ForecastNotAvailable { date: String },
+    #[error("Location {location} is not supported by VisualCrossing, use address, coordinates or postal code")]
+    LocationNotSupported { location: String },
+}
+
+impl WeatherProvider for VisualCrossing {
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => Ok(self
+                .get_forecast_range(location, date, date, offset)?
+                .remove(0)),
+
+            // Timeline without dates starts with current conditions
+            None => self.fetch(location, None, "current")?.current(),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(VisualCrossingError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response = self.fetch_days(location, from, to, "days", offset)?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.daily(&date, offset))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(VisualCrossingError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch_days(location, date, date, "hours", offset)?
+            .hourly(date, offset)
+    }
+
+    fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        let offset = self.fetch(location, None, "current")?.utc_offset().ok_or(
+            VisualCrossingError::TimezoneNotFound {
+                location: location.to_string(),
+            },
+        )?;
+
+        Ok(offset)
+    }
+}
+
+impl Geocoder for VisualCrossing {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        // Visual Crossing has no geocoding API, but it resolves the address of any request
+        let response = self.fetch(&Location::Address(name.to_owned()), None, "current")?;
+
+        Ok(vec![Place::from_address(
+            &response.resolved_address,
+            response.latitude,
+            response.longitude,
+        )])
+    }
+}
+
+impl VisualCrossing {
+    /// Fetch the days of the range [from, to] in the timezone with the UTC offset,
+    /// days of the API are local for the location, so in other timezone
+    /// the hours of the days around the range are fetched to split them by it
+    fn fetch_days(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        include: &str,
+        offset: FixedOffset,
+    ) -> Result<TimelineResponse> {
+        let response = self.fetch(location, Some((from, to)), include)?;
+        if response.utc_offset().map_or(true, |local| local == offset) {
+            return Ok(response);
+        }
+
+        let from = from.pred_opt().unwrap_or(*from);
+        let to = to.succ_opt().unwrap_or(*to);
+        self.fetch(location, Some((&from, &to)), "days,hours")
+    }
+
+    /// Send request to the timeline API for the range of dates (none => from now)
+    /// and deserialize its JSON response, `include` sets the sections of the response
+    fn fetch(
+        &self,
+        location: &Location,
+        dates: Option<(&NaiveDate, &NaiveDate)>,
+        include: &str,
+    ) -> Result<TimelineResponse> {
+        let mut url = format!(
+            "{base_url}/timeline/{location}",
+            base_url = self.base_url,
+            location = location_path(location)?,
+        );
+        if let Some((from, to)) = dates {
+            url.push_str(&format!(
+                "/{from}/{to}",
+                from = from.format("%Y-%m-%d"),
+                to = to.format("%Y-%m-%d")
+            ));
+        }
+
+        let response = self
+            .client
+            .get(url)
+            .query(&[
+                ("key", self.api_key.as_str()),
+                ("unitGroup", "metric"),
+                ("contentType", "json"),
+                ("include", include),
+            ])
+            .send()
+            .map_err(|error| VisualCrossingError::BadResponse { error })?;
+
+        let rejected = !response.status().is_success();
+        let body = response
+            .text()
+            .map_err(|error| VisualCrossingError::BadResponse { error })?;
+
+        // Rejected request has plain text reason instead of data
+        if rejected {
+            return Err(VisualCrossingError::Rejected {
+                reason: body.trim().to_owned(),
+            }
+            .into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| VisualCrossingError::InvalidJson { error })?)
+    }
+}
+
+/// Path segment of the location for Visual Crossing API
+fn location_path(location: &Location) -> Result<String> {
+    let location = match location {
+        Location::Address(address) => address.to_owned(),
+        Location::Coordinates { lat, lon } => format!("{lat},{lon}"),
+        Location::Zip {
+            code,
+            country: Some(country),
+        } => format!("{code},{country}"),
+        Location::Zip {
+            code,
+            country: None,
+        } => code.to_owned(),
+        Location::CityId(_) => {
+            return Err(VisualCrossingError::LocationNotSupported {
+                location: location.to_string(),
+            }
+            .into())
+        }
+    };
+
+    // Address is a part of the path, so it has to be encoded
+    Ok(location
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b',' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        test_util::{fixture, provider_ir, serve},
+        ProviderType,
+    };
+
+    fn paris() -> Location {
+        Location::Coordinates {
+            lat: 48.86,
+            lon: 2.35,
+        }
+    }
+
+    #[test]
+    fn fetch_rejects_unsuccessful_status() {
+        let (base_url, server) = serve(vec![
+            (200, fixture("visual_crossing/current.json")),
+            (401, fixture("visual_crossing/error_invalid_key.txt")),
+        ]);
+        let provider = VisualCrossing::from(provider_ir(ProviderType::VisualCrossing, &base_url));
+        let offset = FixedOffset::east_opt(3600).unwrap();
+
+        assert!(provider.get_weather(&paris(), None, offset).is_ok());
+
+        // Rejected request has plain text reason instead of JSON
+        let error = provider.get_weather(&paris(), None, offset).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<VisualCrossingError>(),
+            Some(VisualCrossingError::Rejected { reason }) if reason == "No account found with API key 'k'"
+        ));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /timeline/48.86,2.35?key=key&"));
+    }
+
+    #[test]
+    fn days_around_are_fetched_for_other_timezone() {
+        let (base_url, server) = serve(vec![
+            (200, fixture("visual_crossing/forecast_days.json")),
+            (200, fixture("visual_crossing/forecast_days.json")),
+            (200, fixture("visual_crossing/forecast_hours.json")),
+        ]);
+        let provider = VisualCrossing::from(provider_ir(ProviderType::VisualCrossing, &base_url));
+        let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+
+        // Days of Paris are in its own timezone
+        let paris_offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let response = provider
+            .fetch_days(&paris(), &date, &date, "days", paris_offset)
+            .unwrap();
+        assert!(response.daily(&date, paris_offset).is_ok());
+
+        // Hours of the day before and after are needed for UTC
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let response = provider
+            .fetch_days(&paris(), &date, &date, "days", utc)
+            .unwrap();
+        assert_eq!(response.hourly(&date, utc).unwrap().len(), 22);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /timeline/48.86,2.35/2026-10-18/2026-10-18?"));
+        assert!(requests[2].starts_with("GET /timeline/48.86,2.35/2026-10-17/2026-10-19?"));
+        assert!(requests[2].contains("include=days%2Chours"));
+    }
+}
@@ -0,0 +1,265 @@
+use chrono::{Duration, FixedOffset, NaiveDate, NaiveTime};
+use eyre::Result;
+use serde::Deserialize;
+
+use super::VisualCrossingError;
+use crate::{
+    forecast::Forecast,
+    provider::{aggregate_hours, kph_to_mps},
+    units::Units,
+};
+
+/// Response of the timeline API, only requested sections are present
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineResponse {
+    /// Full address the location was resolved to
+    pub resolved_address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// UTC offset of the location in hours
+    pub tzoffset: Option<f64>,
+    #[serde(default)]
+    pub days: Vec<Day>,
+    pub current_conditions: Option<Conditions>,
+}
+
+#[derive(Deserialize)]
+pub struct Day {
+    pub datetime: NaiveDate,
+    #[serde(flatten)]
+    pub conditions: Conditions,
+    #[serde(default)]
+    pub hours: Vec<Hour>,
+}
+
+#[derive(Deserialize)]
+pub struct Hour {
+    /// Local time of the location
+    pub datetime: NaiveTime,
+    #[serde(flatten)]
+    pub conditions: Conditions,
+}
+
+/// Weather values, they are aggregated for the whole day in daily forecast
+#[derive(Deserialize)]
+pub struct Conditions {
+    pub temp: f64,
+    pub feelslike: Option<f64>,
+    pub conditions: String,
+    pub humidity: Option<f64>,
+    /// Wind speed in km/h
+    pub windspeed: Option<f64>,
+    pub winddir: Option<f64>,
+    pub pressure: Option<f64>,
+    pub cloudcover: Option<f64>,
+    pub precip: Option<f64>,
+    pub uvindex: Option<f64>,
+}
+
+impl TimelineResponse {
+    /// Cast current conditions to Forecast
+    pub fn current(self) -> Result<Forecast> {
+        Ok(self
+            .current_conditions
+            .ok_or(VisualCrossingError::CurrentNotFound)?
+            .to_forecast())
+    }
+
+    /// UTC offset of the location, dates and times of the days are local for it
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        FixedOffset::east_opt((self.tzoffset? * 3600.0).round() as i32)
+    }
+
+    /// Cast daily forecast for the date to Forecast, days of the API are local for the location,
+    /// so the day is aggregated from its hours in the timezone with other UTC offset
+    pub fn daily(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Forecast> {
+        if self.utc_offset().map_or(false, |local| local != offset) {
+            return Ok(aggregate_hours(date, &self.hourly(date, offset)?).ok_or(
+                VisualCrossingError::ForecastNotFound {
+                    date: date.format("%d.%m.%Y").to_string(),
+                },
+            )?);
+        }
+
+        Ok(Forecast {
+            date: Some(*date),
+            ..self.day(date)?.conditions.to_forecast()
+        })
+    }
+
+    /// Cast hourly forecast for the date in the timezone with the UTC offset to Forecast
+    pub fn hourly(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<Forecast>> {
+        let shift = self.utc_offset().map_or(0, |local| {
+            offset.local_minus_utc() - local.local_minus_utc()
+        });
+
+        let hourly = self
+            .days
+            .iter()
+            .flat_map(|day| {
+                day.hours
+                    .iter()
+                    .map(|hour| (day.datetime.and_time(hour.datetime), hour))
+            })
+            .filter_map(|(time, hour)| {
+                let time = time + Duration::seconds(i64::from(shift));
+
+                (time.date() == *date).then(|| Forecast {
+                    date: Some(time.date()),
+                    time: Some(time.time()),
+                    ..hour.conditions.to_forecast()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if hourly.is_empty() {
+            return Err(VisualCrossingError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(hourly)
+    }
+
+    fn day(&self, date: &NaiveDate) -> Result<&Day> {
+        Ok(self.days.iter().find(|day| day.datetime == *date).ok_or(
+            VisualCrossingError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            },
+        )?)
+    }
+}
+
+impl Conditions {
+    /// Cast weather values to Forecast
+    pub fn to_forecast(&self) -> Forecast {
+        Forecast {
+            units: Units::Metric,
+            date: None,
+            time: None,
+            temp: self.temp,
+            temp_min: None,
+            temp_max: None,
+            condition: self.conditions.clone(),
+            feels_like: self.feelslike,
+            humidity: self.humidity,
+            wind_speed: self.windspeed.map(kph_to_mps),
+            wind_direction: self.winddir,
+            pressure: self.pressure,
+            clouds: self.cloudcover,
+            precipitation: self.precip,
+            uv_index: self.uvindex,
+            sunrise: None,
+            sunset: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Fixtures are recorded for Paris (UTC+02:00)
+    fn paris() -> FixedOffset {
+        FixedOffset::east_opt(2 * 3600).unwrap()
+    }
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    #[test]
+    fn current() {
+        let response: TimelineResponse =
+            parse_json(&fixture("visual_crossing/current.json")).unwrap();
+
+        assert_eq!(response.resolved_address, "Paris, Île-de-France, France");
+        assert_eq!(response.tzoffset, Some(2.0));
+
+        let forecast = response.current().unwrap();
+
+        assert_eq!(forecast.temp, 13.4);
+        assert_eq!(forecast.condition, "Partly cloudy");
+        assert_eq!(forecast.feels_like, Some(11.9));
+        // Wind speed is converted from km/h
+        assert_eq!(forecast.wind_speed, Some(5.0));
+    }
+
+    #[test]
+    fn daily() {
+        let response: TimelineResponse =
+            parse_json(&fixture("visual_crossing/forecast_days.json")).unwrap();
+        let forecast = response.daily(&date(17), paris()).unwrap();
+
+        assert_eq!(forecast.date, Some(date(17)));
+        assert_eq!(forecast.temp, 12.9);
+        assert_eq!(forecast.condition, "Rain, Partially cloudy");
+        assert_eq!(forecast.precipitation, Some(4.2));
+
+        assert!(response.daily(&date(20), paris()).is_err());
+    }
+
+    #[test]
+    fn daily_in_other_timezone() {
+        let response: TimelineResponse =
+            parse_json(&fixture("visual_crossing/forecast_hours.json")).unwrap();
+        let forecast = response.daily(&date(18), utc()).unwrap();
+
+        // The day is aggregated from its hours in UTC, the first two hours of Paris are the 17th
+        assert_eq!(forecast.date, Some(date(18)));
+        assert_eq!(forecast.temp, 13.75);
+        assert_eq!(forecast.temp_min, Some(10.6));
+        assert_eq!(forecast.temp_max, Some(16.9));
+        assert_eq!(forecast.condition, "Partially cloudy");
+        assert_eq!(forecast.wind_speed, Some(5.0));
+
+        // Days without hours can not be split
+        let days: TimelineResponse =
+            parse_json(&fixture("visual_crossing/forecast_days.json")).unwrap();
+        assert!(days.daily(&date(18), utc()).is_err());
+    }
+
+    #[test]
+    fn hourly() {
+        let response: TimelineResponse =
+            parse_json(&fixture("visual_crossing/forecast_hours.json")).unwrap();
+        let hourly = response.hourly(&date(18), paris()).unwrap();
+
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].date, Some(date(18)));
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].condition, "Overcast");
+        assert_eq!(hourly[0].precipitation, Some(0.1));
+
+        // Hours are split into days of UTC, 2 hours behind Paris
+        let hourly = response.hourly(&date(18), utc()).unwrap();
+        assert_eq!(hourly.len(), 22);
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].temp, 10.6);
+        assert_eq!(response.hourly(&date(17), utc()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn missing_section() {
+        let days: TimelineResponse =
+            parse_json(&fixture("visual_crossing/forecast_days.json")).unwrap();
+
+        // Days are requested without hours and current conditions
+        assert!(days.hourly(&date(18), paris()).is_err());
+        assert!(days.current().is_err());
+    }
+
+    #[test]
+    fn changed_schema_is_reported_with_path() {
+        let body = fixture("visual_crossing/current.json").replace("\"temp\"", "\"temperature\"");
+        let error = parse_json::<TimelineResponse>(&body).err().unwrap();
+
+        assert_eq!(error.path().to_string(), "currentConditions");
+    }
+}
@@ -1,149 +1,328 @@
-use chrono::{Local, NaiveDate};
+use chrono::{FixedOffset, NaiveDate};
 use eyre::Result;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use std::ops::RangeInclusive;
 use thiserror::Error;
 
-use super::{ProviderIR, WeatherProvider};
-use crate::forecast::Forecast;
+use self::response::{
+    AstronomyResponse, CurrentResponse, ErrorResponse, ForecastResponse, SearchPlace,
+};
+use super::{
+    dates_out_of_horizon, format_dates, parse_json, Geocoder, JsonError, ProviderIR,
+    WeatherProvider,
+};
+use crate::{
+    air_quality::AirQuality,
+    alert::Alert,
+    astronomy::Astronomy,
+    date,
+    forecast::Forecast,
+    location::{Location, Place},
+};
+
+mod response;
 
 /// Provider for WeatherApi
 pub struct WeatherApi {
     api_key: String,
+    base_url: String,
+    client: Client,
 }
 
+/// Default base URL of WeatherApi API
+const DEFAULT_BASE_URL: &str = "http://api.weatherapi.com/v1";
+
+/// Days after today covered by the forecast, it goes on for 13 days after now
+const FORECAST_HORIZON: RangeInclusive<i64> = 0..=13;
+
+/// Error code of history request beyond the limit of the API key plan
+const HISTORY_LIMITED_CODE: u32 = 1008;
+
 /// Cast from intermediate representation
 impl From<ProviderIR> for WeatherApi {
     fn from(value: ProviderIR) -> Self {
-        WeatherApi { api_key: value.key }
+        WeatherApi {
+            api_key: value.key,
+            base_url: value
+                .base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_BASE_URL)
+                .trim_end_matches('/')
+                .to_owned(),
+            client: Client::new(),
+        }
     }
 }
 
-/// Wrapper for casting into Forecast
-struct WeatherApiJson<'a> {
-    body: serde_json::Value,
-    forecast_date: Option<&'a NaiveDate>,
-}
-
 /// Errors for WeatherApi
 #[derive(Error, Debug)]
 pub enum WeatherApiError {
     #[error("Bad response with error '{error}'")]
-    BadResponse { error: reqwest::Error },
-    #[error("Something wrong with {field} in json")]
-    InvalidJsonFormat { field: String },
+    BadResponse {
+        #[source]
+        error: reqwest::Error,
+    },
     #[error("Json is invalid with error '{error}'")]
-    InvalidJson { error: reqwest::Error },
+    InvalidJson { error: JsonError },
+    #[error("Request is rejected with reason '{reason}'")]
+    Rejected { code: u32, reason: String },
     #[error("Forecast is not found for date {date}")]
     ForecastNotFound { date: String },
     #[error("Forecast with provider WeatherApi is not available for {date}")]
     ForecastNotAvailable { date: String },
+    #[error("Air quality is not found for location {location}")]
+    AirQualityNotFound { location: String },
+    #[error("Local time is not found for location {location}")]
+    LocalTimeNotFound { location: String },
+    #[error("History with provider WeatherApi is not available for {date}, it goes back to 01.01.2010 (the last 7 days on the free plan)")]
+    HistoryNotAvailable { date: String },
 }
 
 impl WeatherProvider for WeatherApi {
-    fn get_weather(&self, address: &str, date: Option<&NaiveDate>) -> Result<Forecast> {
-        let res: WeatherApiJson = WeatherApiJson {
-            body: reqwest::blocking::get(match date {
-                Some(date) => {
-                    let now = Local::now().date_naive();
-
-                    // WeatherApi forecast give current weather if days = 1, so we should use offset
-                    let days = (*date - now).num_days() + 1;
-
-                    // WeatherApi can get forecast up to 13 days after now
-                    if !(2..=14).contains(&days) {
-                        return Err(WeatherApiError::ForecastNotAvailable { date: date.format("%d.%m.%Y").to_string() }.into());
-                    }
+    fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn get_weather(
+        &self,
+        location: &Location,
+        date: Option<&NaiveDate>,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        match date {
+            Some(date) => {
+                let now = date::today(offset);
 
-                    format!(
-                        // API for forecast up to 13 days
-                        "http://api.weatherapi.com/v1/forecast.json?key={key}&q={address}&days={days}&aqi=no&alerts=no",
-                        key = self.api_key,
-                    )
+                // WeatherApi forecast for today is the current weather, so only later days are here
+                let after = (*date - now).num_days();
+                if after == 0 || !FORECAST_HORIZON.contains(&after) {
+                    return Err(WeatherApiError::ForecastNotAvailable {
+                        date: date.format("%d.%m.%Y").to_string(),
+                    }
+                    .into());
                 }
 
-                None => format!(
-                    // API for current weather
-                    "http://api.weatherapi.com/v1/current.json?key={key}&q={address}&aqi=no",
-                    key = self.api_key
-                ),
-            })
-            .map_err(|error| WeatherApiError::BadResponse { error })?
-            .json()
-            .map_err(|error| WeatherApiError::InvalidJson { error })?,
-
-            forecast_date: date,
+                self.fetch::<ForecastResponse>(
+                    "forecast.json",
+                    &forecast_query(location, forecast_days(date, offset)),
+                )?
+                .forecast(date, offset)
+            }
+
+            // API for current weather
+            None => Ok(self
+                .fetch::<CurrentResponse>(
+                    "current.json",
+                    &[("q", location_query(location)), ("aqi", "no".to_owned())],
+                )?
+                .into_forecast()),
+        }
+    }
+
+    fn get_forecast_range(
+        &self,
+        location: &Location,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(from, to, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(WeatherApiError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        let response = self.fetch::<ForecastResponse>(
+            "forecast.json",
+            &forecast_query(location, forecast_days(to, offset)),
+        )?;
+
+        from.iter_days()
+            .take_while(|date| date <= to)
+            .map(|date| response.forecast(&date, offset))
+            .collect()
+    }
+
+    fn get_hourly(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Vec<Forecast>> {
+        let unavailable = dates_out_of_horizon(date, date, FORECAST_HORIZON, offset);
+        if !unavailable.is_empty() {
+            return Err(WeatherApiError::ForecastNotAvailable {
+                date: format_dates(&unavailable),
+            }
+            .into());
+        }
+
+        self.fetch::<ForecastResponse>(
+            "forecast.json",
+            &forecast_query(location, forecast_days(date, offset)),
+        )?
+        .hourly(date, offset)
+    }
+
+    fn get_history(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        offset: FixedOffset,
+    ) -> Result<Forecast> {
+        let not_available = || WeatherApiError::HistoryNotAvailable {
+            date: date.format("%d.%m.%Y").to_string(),
         };
 
-        res.into_forecast()
+        // WeatherApi keeps history since 2010
+        if *date < NaiveDate::from_ymd_opt(2010, 1, 1).unwrap_or(NaiveDate::MIN) {
+            return Err(not_available().into());
+        }
+
+        // API for history has the same format as the one for forecast
+        let response = self
+            .fetch::<ForecastResponse>(
+                "history.json",
+                &[
+                    ("q", location_query(location)),
+                    ("dt", date.format("%Y-%m-%d").to_string()),
+                ],
+            )
+            .map_err(|error| match error.downcast_ref::<WeatherApiError>() {
+                // Plan of the API key limits how far back the history goes
+                Some(WeatherApiError::Rejected {
+                    code: HISTORY_LIMITED_CODE,
+                    ..
+                }) => not_available().into(),
+                _ => error,
+            })?;
+
+        // History has only the day of the location, so it is not split by other timezone
+        let local_offset = response.location.utc_offset().unwrap_or(offset);
+        response.forecast(date, local_offset)
+    }
+
+    fn get_utc_offset(&self, location: &Location) -> Result<FixedOffset> {
+        Ok(self
+            .fetch::<CurrentResponse>(
+                "current.json",
+                &[("q", location_query(location)), ("aqi", "no".to_owned())],
+            )?
+            .location
+            .utc_offset()
+            .ok_or(WeatherApiError::LocalTimeNotFound {
+                location: location.to_string(),
+            })?)
+    }
+
+    fn get_alerts(&self, location: &Location) -> Result<Vec<Alert>> {
+        // Alerts are given only with forecast
+        Ok(self
+            .fetch::<ForecastResponse>(
+                "forecast.json",
+                &[
+                    ("q", location_query(location)),
+                    ("days", "1".to_owned()),
+                    ("aqi", "no".to_owned()),
+                    ("alerts", "yes".to_owned()),
+                ],
+            )?
+            .into_alerts())
+    }
+
+    fn get_air_quality(&self, location: &Location) -> Result<AirQuality> {
+        Ok(self
+            .fetch::<CurrentResponse>(
+                "current.json",
+                &[("q", location_query(location)), ("aqi", "yes".to_owned())],
+            )?
+            .air_quality()
+            .ok_or(WeatherApiError::AirQualityNotFound {
+                location: location.to_string(),
+            })?)
+    }
+
+    fn get_astronomy(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        _offset: FixedOffset,
+    ) -> Result<Astronomy> {
+        Ok(self
+            .fetch::<AstronomyResponse>(
+                "astronomy.json",
+                &[
+                    ("q", location_query(location)),
+                    ("dt", date.format("%Y-%m-%d").to_string()),
+                ],
+            )?
+            .astronomy
+            .astro
+            .to_astronomy())
     }
 }
 
-impl<'a> WeatherApiJson<'a> {
-    /// Cast WeatherApiJson to Forecast
-    pub fn into_forecast(self) -> Result<Forecast> {
-        Ok(match self.forecast_date {
-            // Parse JSON for current weather
-            None => {
-                let current = self.body.get("current");
-
-                Forecast {
-                    temp: current
-                        .and_then(|v| v.get("temp_c"))
-                        .and_then(|v| v.as_f64())
-                        .ok_or(WeatherApiError::InvalidJsonFormat {
-                            field: "current:temp_c".to_string(),
-                        })?,
-
-                    condition: current
-                        .and_then(|v| v.get("condition"))
-                        .and_then(|v| v.get("text"))
-                        .and_then(|v| v.as_str())
-                        .ok_or(WeatherApiError::InvalidJsonFormat {
-                            field: "current:condition:text".to_string(),
-                        })?
-                        .to_owned(),
-                }
-            }
+impl Geocoder for WeatherApi {
+    fn geocode(&self, name: &str) -> Result<Vec<Place>> {
+        Ok(self
+            .fetch::<Vec<SearchPlace>>("search.json", &[("q", name.to_owned())])?
+            .into_iter()
+            .map(Place::from)
+            .collect())
+    }
+}
 
-            // Parse JSON for forecast
-            Some(date) => {
-                let forecast = self
-                    .body
-                    .get("forecast")
-                    .and_then(|v| v.get("forecastday"))
-                    .and_then(serde_json::Value::as_array)
-                    .and_then(|v| {
-                        v.iter().find(|v| {
-                            if let Some(v) = v.get("date").and_then(|v| v.as_str()) {
-                                *v == date.format("%Y-%m-%d").to_string()
-                            } else {
-                                false
-                            }
-                        })
-                    })
-                    .ok_or(WeatherApiError::ForecastNotFound {
-                        date: date.format("%d.%m.%Y").to_string(),
-                    })?;
-
-                Forecast {
-                    temp: forecast
-                        .get("day")
-                        .and_then(|v| v.get("avgtemp_c"))
-                        .and_then(|v| v.as_f64())
-                        .ok_or(WeatherApiError::InvalidJsonFormat {
-                            field: "day:temp_c".to_string(),
-                        })?,
-
-                    condition: forecast
-                        .get("day")
-                        .and_then(|v| v.get("condition"))
-                        .and_then(|v| v.get("text"))
-                        .and_then(|v| v.as_str())
-                        .ok_or(WeatherApiError::InvalidJsonFormat {
-                            field: "day:condition:text".to_string(),
-                        })?
-                        .to_owned(),
-                }
+impl WeatherApi {
+    /// Send request to the API endpoint and deserialize its JSON response
+    fn fetch<T: DeserializeOwned>(&self, endpoint: &str, query: &[(&str, String)]) -> Result<T> {
+        let body = self
+            .client
+            .get(format!("{base_url}/{endpoint}", base_url = self.base_url))
+            .query(&[("key", &self.api_key)])
+            .query(query)
+            .send()
+            .and_then(|response| response.text())
+            .map_err(|error| WeatherApiError::BadResponse { error })?;
+
+        // Rejected request has error code and message instead of data
+        if let Ok(ErrorResponse { error }) = serde_json::from_str(&body) {
+            return Err(WeatherApiError::Rejected {
+                code: error.code,
+                reason: error.message,
             }
-        })
+            .into());
+        }
+
+        Ok(parse_json(&body).map_err(|error| WeatherApiError::InvalidJson { error })?)
+    }
+}
+
+/// Query parameter `q` of the location for WeatherApi
+fn location_query(location: &Location) -> String {
+    match location {
+        Location::Address(address) => address.to_owned(),
+        Location::Coordinates { lat, lon } => format!("{lat},{lon}"),
+        // WeatherApi detects country by format of the postal code itself
+        Location::Zip { code, .. } => code.to_owned(),
+        Location::CityId(id) => format!("id:{id}"),
     }
 }
+
+/// Number of days of the forecast (from today) covering the date in the timezone
+/// with the UTC offset, the next day is included for locations behind the timezone
+fn forecast_days(date: &NaiveDate, offset: FixedOffset) -> i64 {
+    ((*date - date::today(offset)).num_days() + 2).min(FORECAST_HORIZON.end() + 1)
+}
+
+/// Query parameters of API for forecast up to 14 days
+fn forecast_query(location: &Location, days: i64) -> [(&'static str, String); 4] {
+    [
+        ("q", location_query(location)),
+        ("days", days.to_string()),
+        ("aqi", "no".to_owned()),
+        ("alerts", "no".to_owned()),
+    ]
+}
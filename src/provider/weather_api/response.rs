@@ -0,0 +1,542 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use eyre::Result;
+use serde::{Deserialize, Deserializer};
+
+use super::WeatherApiError;
+use crate::{
+    air_quality::AirQuality,
+    alert::{Alert, Severity},
+    astronomy::{Astronomy, MoonPhase},
+    date,
+    forecast::Forecast,
+    location::Place,
+    provider::{aggregate_hours, kph_to_mps},
+    units::Units,
+};
+
+/// Response of the current weather API
+#[derive(Deserialize)]
+pub struct CurrentResponse {
+    pub location: ResponseLocation,
+    pub current: Current,
+}
+
+/// Response of the forecast API
+#[derive(Deserialize)]
+pub struct ForecastResponse {
+    pub location: ResponseLocation,
+    pub forecast: ForecastDays,
+    /// Present only if alerts are requested
+    pub alerts: Option<Alerts>,
+}
+
+/// Response of the astronomy API
+#[derive(Deserialize)]
+pub struct AstronomyResponse {
+    pub astronomy: AstronomyDay,
+}
+
+#[derive(Deserialize)]
+pub struct AstronomyDay {
+    pub astro: Astro,
+}
+
+/// Item of the response of the search API
+#[derive(Deserialize)]
+pub struct SearchPlace {
+    pub name: String,
+    pub region: Option<String>,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Error response of any API
+#[derive(Deserialize)]
+pub struct ErrorResponse {
+    pub error: ApiError,
+}
+
+#[derive(Deserialize)]
+pub struct ApiError {
+    pub code: u32,
+    pub message: String,
+}
+
+/// Location of the response with its local time
+#[derive(Deserialize)]
+pub struct ResponseLocation {
+    /// Unix timestamp of the local time
+    pub localtime_epoch: i64,
+    /// Local time of the location (without seconds)
+    #[serde(deserialize_with = "deserialize_time")]
+    pub localtime: NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct Current {
+    pub temp_c: f64,
+    pub feelslike_c: Option<f64>,
+    pub condition: Condition,
+    pub humidity: Option<f64>,
+    pub wind_kph: Option<f64>,
+    pub wind_degree: Option<f64>,
+    pub pressure_mb: Option<f64>,
+    pub cloud: Option<f64>,
+    pub precip_mm: Option<f64>,
+    pub uv: Option<f64>,
+    /// Present only if air quality is requested
+    pub air_quality: Option<ApiAirQuality>,
+}
+
+/// Concentrations of pollutants in µg/m³
+#[derive(Deserialize)]
+pub struct ApiAirQuality {
+    pub co: Option<f64>,
+    pub no2: Option<f64>,
+    pub o3: Option<f64>,
+    pub so2: Option<f64>,
+    pub pm2_5: Option<f64>,
+    pub pm10: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastDays {
+    pub forecastday: Vec<ForecastDay>,
+}
+
+#[derive(Deserialize)]
+pub struct ForecastDay {
+    pub date: NaiveDate,
+    pub day: Day,
+    pub astro: Option<Astro>,
+    #[serde(default)]
+    pub hour: Vec<Hour>,
+}
+
+/// Aggregated weather for the whole day
+#[derive(Deserialize)]
+pub struct Day {
+    pub avgtemp_c: f64,
+    pub mintemp_c: Option<f64>,
+    pub maxtemp_c: Option<f64>,
+    pub condition: Condition,
+    pub avghumidity: Option<f64>,
+    pub maxwind_kph: Option<f64>,
+    pub totalprecip_mm: Option<f64>,
+    pub uv: Option<f64>,
+}
+
+/// Weather for one hour of the day
+#[derive(Deserialize)]
+pub struct Hour {
+    /// Unix timestamp of the hour
+    pub time_epoch: i64,
+    /// Local time of the location
+    #[serde(deserialize_with = "deserialize_time")]
+    pub time: NaiveDateTime,
+    pub temp_c: f64,
+    pub feelslike_c: Option<f64>,
+    pub condition: Condition,
+    pub humidity: Option<f64>,
+    pub wind_kph: Option<f64>,
+    pub wind_degree: Option<f64>,
+    pub pressure_mb: Option<f64>,
+    pub cloud: Option<f64>,
+    pub precip_mm: Option<f64>,
+    pub uv: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct Condition {
+    pub text: String,
+}
+
+/// Sun and moon of the day, times are local to the location (e.g. 07:12 AM)
+#[derive(Deserialize)]
+pub struct Astro {
+    /// "No sunrise" if the sun does not rise that day
+    pub sunrise: Option<String>,
+    /// "No sunset" if the sun does not set that day
+    pub sunset: Option<String>,
+    pub moon_phase: Option<String>,
+    /// Illuminated part of the moon disc in %, older versions of the API give it as string
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub moon_illumination: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct Alerts {
+    pub alert: Vec<ApiAlert>,
+}
+
+/// Alert issued by the government agency
+#[derive(Deserialize)]
+pub struct ApiAlert {
+    pub headline: Option<String>,
+    pub event: Option<String>,
+    /// Severity of Common Alerting Protocol (e.g. Moderate)
+    pub severity: Option<String>,
+    pub effective: Option<DateTime<FixedOffset>>,
+    pub expires: Option<DateTime<FixedOffset>>,
+    pub desc: Option<String>,
+}
+
+impl CurrentResponse {
+    /// Cast current weather to Forecast
+    pub fn into_forecast(self) -> Forecast {
+        let current = self.current;
+
+        Forecast {
+            units: Units::Metric,
+            date: None,
+            time: None,
+            temp: current.temp_c,
+            temp_min: None,
+            temp_max: None,
+            condition: current.condition.text,
+            feels_like: current.feelslike_c,
+            humidity: current.humidity,
+            wind_speed: current.wind_kph.map(kph_to_mps),
+            wind_direction: current.wind_degree,
+            pressure: current.pressure_mb,
+            clouds: current.cloud,
+            precipitation: current.precip_mm,
+            uv_index: current.uv,
+            sunrise: None,
+            sunset: None,
+        }
+    }
+
+    /// Cast air quality of current weather to AirQuality
+    pub fn air_quality(&self) -> Option<AirQuality> {
+        let air = self.current.air_quality.as_ref()?;
+
+        // WeatherApi rates air quality by several scales, the common one is calculated instead
+        Some(AirQuality::from_concentrations(
+            air.pm2_5, air.pm10, air.o3, air.no2, air.so2, air.co,
+        ))
+    }
+}
+
+impl ResponseLocation {
+    /// UTC offset of the location as difference between its local time and UTC one
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        let utc = NaiveDateTime::from_timestamp_opt(self.localtime_epoch, 0)?;
+
+        // Local time has no seconds, offsets of timezones are multiples of 15 minutes
+        let quarters = ((self.localtime - utc).num_seconds() as f64 / 900.0).round() as i32;
+
+        FixedOffset::east_opt(quarters * 900)
+    }
+}
+
+impl ForecastResponse {
+    /// Cast forecast for the date to Forecast, days of the API are local for the location,
+    /// so the day is aggregated from its hours in the timezone with other UTC offset
+    pub fn forecast(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Forecast> {
+        let not_found = || WeatherApiError::ForecastNotFound {
+            date: date.format("%d.%m.%Y").to_string(),
+        };
+        let local_offset = self.location.utc_offset().unwrap_or(offset);
+        let forecast_day = self
+            .forecast
+            .forecastday
+            .iter()
+            .find(|forecast_day| forecast_day.date == *date);
+        let astronomy = forecast_day
+            .and_then(|forecast_day| forecast_day.astro.as_ref())
+            .map(Astro::to_astronomy)
+            .unwrap_or_default();
+
+        if local_offset != offset {
+            // Sunrise and sunset are in local time of the location
+            let shift = Duration::seconds(i64::from(
+                offset.local_minus_utc() - local_offset.local_minus_utc(),
+            ));
+            let shifted = |time: Option<NaiveTime>| Some(time?.overflowing_add_signed(shift).0);
+
+            return Ok(Forecast {
+                sunrise: shifted(astronomy.sunrise),
+                sunset: shifted(astronomy.sunset),
+                ..aggregate_hours(date, &self.hourly(date, offset)?).ok_or_else(not_found)?
+            });
+        }
+
+        let day = &forecast_day.ok_or_else(not_found)?.day;
+
+        Ok(Forecast {
+            date: Some(*date),
+            temp: day.avgtemp_c,
+            temp_min: day.mintemp_c,
+            temp_max: day.maxtemp_c,
+            condition: day.condition.text.clone(),
+            // Daily forecast has only aggregated values
+            humidity: day.avghumidity,
+            wind_speed: day.maxwind_kph.map(kph_to_mps),
+            precipitation: day.totalprecip_mm,
+            uv_index: day.uv,
+            sunrise: astronomy.sunrise,
+            sunset: astronomy.sunset,
+            ..Default::default()
+        })
+    }
+
+    /// Cast hourly forecast for the date in the timezone with the UTC offset to Forecast
+    pub fn hourly(&self, date: &NaiveDate, offset: FixedOffset) -> Result<Vec<Forecast>> {
+        let hourly = self
+            .forecast
+            .forecastday
+            .iter()
+            .flat_map(|forecast_day| &forecast_day.hour)
+            .filter_map(|hour| {
+                let time = date::to_local(&Utc.timestamp_opt(hour.time_epoch, 0).single()?, offset);
+
+                (time.date() == *date).then(|| Forecast {
+                    units: Units::Metric,
+                    date: Some(time.date()),
+                    time: Some(time.time()),
+                    temp: hour.temp_c,
+                    temp_min: None,
+                    temp_max: None,
+                    condition: hour.condition.text.clone(),
+                    feels_like: hour.feelslike_c,
+                    humidity: hour.humidity,
+                    wind_speed: hour.wind_kph.map(kph_to_mps),
+                    wind_direction: hour.wind_degree,
+                    pressure: hour.pressure_mb,
+                    clouds: hour.cloud,
+                    precipitation: hour.precip_mm,
+                    uv_index: hour.uv,
+                    sunrise: None,
+                    sunset: None,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if hourly.is_empty() {
+            return Err(WeatherApiError::ForecastNotFound {
+                date: date.format("%d.%m.%Y").to_string(),
+            }
+            .into());
+        }
+
+        Ok(hourly)
+    }
+
+    /// Cast alerts of the response to Alert
+    pub fn into_alerts(self) -> Vec<Alert> {
+        self.alerts
+            .map(|alerts| alerts.alert)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|alert| Alert {
+                // Some agencies give only the headline
+                event: alert
+                    .event
+                    .filter(|event| !event.is_empty())
+                    .or(alert.headline)
+                    .unwrap_or("Unknown".to_owned()),
+                severity: Severity::parse(alert.severity.as_deref().unwrap_or_default()),
+                start: alert.effective,
+                end: alert.expires,
+                description: alert.desc.unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+impl Astro {
+    /// Cast sun and moon of the day to Astronomy
+    pub fn to_astronomy(&self) -> Astronomy {
+        let time = |time: &Option<String>| {
+            NaiveTime::parse_from_str(time.as_deref()?.trim(), "%I:%M %p").ok()
+        };
+
+        Astronomy {
+            sunrise: time(&self.sunrise),
+            sunset: time(&self.sunset),
+            moon_phase: self.moon_phase.as_deref().and_then(MoonPhase::parse),
+            moon_illumination: self.moon_illumination,
+        }
+    }
+}
+
+impl From<SearchPlace> for Place {
+    fn from(value: SearchPlace) -> Self {
+        Place {
+            name: value.name,
+            region: value.region,
+            country: value.country,
+            lat: value.lat,
+            lon: value.lon,
+        }
+    }
+}
+
+/// Deserialize local time in WeatherApi format (e.g. 2023-04-02 13:00)
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+    let time = String::deserialize(deserializer)?;
+
+    NaiveDateTime::parse_from_str(&time, "%Y-%m-%d %H:%M").map_err(serde::de::Error::custom)
+}
+
+/// Deserialize number which can be given as string (e.g. "72")
+fn deserialize_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Number(f64),
+        Text(String),
+    }
+
+    Ok(match Option::<Number>::deserialize(deserializer)? {
+        Some(Number::Number(number)) => Some(number),
+        Some(Number::Text(text)) => text.trim().parse().ok(),
+        None => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{
+        parse_json,
+        test_util::{date, fixture, time},
+    };
+
+    /// Fixtures are recorded for London (UTC+01:00)
+    fn london() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn tokyo() -> FixedOffset {
+        FixedOffset::east_opt(9 * 3600).unwrap()
+    }
+
+    #[test]
+    fn current() {
+        let response: CurrentResponse = parse_json(&fixture("weather_api/current.json")).unwrap();
+
+        assert_eq!(response.location.utc_offset(), FixedOffset::east_opt(3600));
+
+        let forecast = response.into_forecast();
+
+        assert_eq!(forecast.temp, 14.2);
+        assert_eq!(forecast.condition, "Partly cloudy");
+        assert_eq!(forecast.feels_like, Some(12.9));
+        assert_eq!(forecast.wind_speed, Some(4.19));
+        assert_eq!(forecast.pressure, Some(1012.0));
+        assert_eq!(forecast.uv_index, Some(3.0));
+    }
+
+    #[test]
+    fn forecast() {
+        let response: ForecastResponse = parse_json(&fixture("weather_api/forecast.json")).unwrap();
+        let forecast = response.forecast(&date(17), london()).unwrap();
+
+        assert_eq!(forecast.date, Some(date(17)));
+        assert_eq!(forecast.temp, 13.1);
+        assert_eq!(forecast.temp_min, Some(9.4));
+        assert_eq!(forecast.temp_max, Some(15.8));
+        assert_eq!(forecast.condition, "Patchy rain possible");
+        assert_eq!(forecast.humidity, Some(81.0));
+        assert_eq!(forecast.precipitation, Some(2.4));
+        assert_eq!(forecast.sunrise, time(7, 24));
+        assert_eq!(forecast.sunset, time(18, 5));
+
+        assert!(response.forecast(&date(19), london()).is_err());
+    }
+
+    #[test]
+    fn forecast_in_other_timezone() {
+        let response: ForecastResponse = parse_json(&fixture("weather_api/forecast.json")).unwrap();
+        let forecast = response.forecast(&date(18), tokyo()).unwrap();
+
+        // The day is aggregated from its hours in Tokyo (from 16:00 of the 17th in London)
+        assert_eq!(forecast.date, Some(date(18)));
+        assert_eq!(forecast.temp, 12.22);
+        assert_eq!(forecast.temp_min, Some(8.1));
+        assert_eq!(forecast.temp_max, Some(14.9));
+        assert_eq!(forecast.condition, "Partly cloudy");
+        assert_eq!(forecast.wind_direction, Some(220.0));
+        assert_eq!(forecast.wind_speed, Some(3.89));
+        assert_eq!(forecast.precipitation, Some(2.4));
+        // Sunrise and sunset are moved to the timezone too
+        assert_eq!(forecast.sunrise, time(15, 24));
+        assert_eq!(forecast.sunset, time(2, 5));
+    }
+
+    #[test]
+    fn hourly() {
+        let response: ForecastResponse = parse_json(&fixture("weather_api/forecast.json")).unwrap();
+        let hourly = response.hourly(&date(18), london()).unwrap();
+
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].condition, "Light rain");
+        assert_eq!(hourly[0].precipitation, Some(0.6));
+
+        // Hours are split into days of Tokyo, 8 hours ahead of London
+        let hourly = response.hourly(&date(18), tokyo()).unwrap();
+        assert_eq!(hourly.len(), 24);
+        assert_eq!(hourly[0].date, Some(date(18)));
+        assert_eq!(hourly[0].time, time(0, 0));
+        assert_eq!(hourly[0].temp, 14.9);
+        assert_eq!(response.hourly(&date(17), tokyo()).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn alerts() {
+        let response: ForecastResponse = parse_json(&fixture("weather_api/forecast.json")).unwrap();
+        let alerts = response.into_alerts();
+
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].event, "Yellow warning for rain");
+        assert_eq!(alerts[0].severity, Severity::Moderate);
+        // Empty event is replaced with the headline
+        assert_eq!(alerts[1].event, "Met Office wind warning");
+        assert_eq!(alerts[1].severity, Severity::Minor);
+    }
+
+    #[test]
+    fn air_quality() {
+        let response: CurrentResponse = parse_json(&fixture("weather_api/current.json")).unwrap();
+        let air_quality = response.air_quality().unwrap();
+
+        assert_eq!(air_quality.pm2_5, Some(7.9));
+        assert!(air_quality.aqi.is_some());
+    }
+
+    #[test]
+    fn astronomy() {
+        let response: AstronomyResponse =
+            parse_json(&fixture("weather_api/astronomy.json")).unwrap();
+        let astronomy = response.astronomy.astro.to_astronomy();
+
+        assert_eq!(astronomy.sunrise, time(7, 24));
+        assert_eq!(astronomy.sunset, time(18, 5));
+        assert_eq!(astronomy.moon_phase, Some(MoonPhase::WaxingCrescent));
+        // Illumination is given as string here
+        assert_eq!(astronomy.moon_illumination, Some(23.0));
+    }
+
+    #[test]
+    fn search() {
+        let places: Vec<SearchPlace> = parse_json(&fixture("weather_api/search.json")).unwrap();
+        let places: Vec<Place> = places.into_iter().map(Place::from).collect();
+
+        assert_eq!(places.len(), 2);
+        assert_eq!(places[1].region.as_deref(), Some("Ontario"));
+        assert_eq!(places[1].country, "Canada");
+    }
+
+    #[test]
+    fn error() {
+        let response: ErrorResponse =
+            parse_json(&fixture("weather_api/error_history_limited.json")).unwrap();
+
+        assert_eq!(response.error.code, 1008);
+        assert!(response.error.message.contains("history"));
+    }
+}
@@ -1,9 +1,12 @@
 use eyre::Result;
+use std::env;
 use thiserror::Error;
 
 use crate::{
     config::Config,
-    provider::{Provider, ProviderIR, ProviderType},
+    location::Location,
+    provider::{Provider, ProviderIR, ProviderType, PROVIDER_TYPE_MAP},
+    units::Units,
 };
 
 pub mod json_loader;
@@ -12,8 +15,10 @@ pub mod json_loader;
 #[allow(dead_code)]
 #[derive(Error, Debug)]
 pub enum LoaderError {
-    #[error("API key for provider {provider:?} is not found")]
+    #[error("API key for provider {provider:?} is not found, set it with `configure --provider {provider:?} --api-key <API_KEY>`")]
     APIKeyNotFound { provider: ProviderType },
+    #[error("Contact for provider {provider:?} is not found, set it with `configure --provider {provider:?} --contact <EMAIL>`")]
+    ContactNotFound { provider: ProviderType },
     #[error("Default provider has not set")]
     DefaultProviderNotSet,
 }
@@ -43,16 +48,78 @@ pub trait ProviderLoader {
     /// Set API key for specified weather provider
     fn set_provider_key(&mut self, provider: ProviderType, key: &str) -> Result<()>;
 
+    /// Set contact for specified weather provider
+    fn set_provider_contact(&mut self, provider: ProviderType, contact: &str) -> Result<()>;
+
+    /// Set base URL for specified weather provider
+    fn set_provider_base_url(&mut self, provider: ProviderType, base_url: &str) -> Result<()>;
+
+    /// Set default unit system
+    fn set_units(&mut self, units: Units) -> Result<()>;
+
+    /// Save location under the name
+    fn add_location(&mut self, name: &str, location: Location) -> Result<()>;
+
+    /// Remove named location, it stops being the default one
+    fn remove_location(&mut self, name: &str) -> Result<()>;
+
+    /// Set default named location, none => no default location
+    fn set_default_location(&mut self, name: Option<&str>) -> Result<()>;
+
+    /// Set providers to fall back to in order
+    fn set_priority(&mut self, priority: Vec<ProviderType>) -> Result<()>;
+
+    /// Set weight of provider in ensemble forecast
+    fn set_weight(&mut self, provider: ProviderType, weight: f64) -> Result<()>;
+
     /// Get provider by provider type
     fn get_provider(&self, provider: ProviderType) -> Result<Provider> {
-        let key = self
-            .get_config()?
-            .keys
-            .get(&provider)
-            .ok_or(LoaderError::APIKeyNotFound { provider })?
-            .clone();
-
-        Ok(ProviderIR { key, provider }.into())
+        let config = self.get_config()?;
+
+        let key = match config.keys.get(&provider) {
+            Some(key) => key.clone(),
+            None if !provider.requires_key() => String::new(),
+            None => return Err(LoaderError::APIKeyNotFound { provider }.into()),
+        };
+
+        let contact = match config.contacts.get(&provider) {
+            Some(contact) => contact.clone(),
+            None if !provider.requires_contact() => String::new(),
+            None => return Err(LoaderError::ContactNotFound { provider }.into()),
+        };
+
+        // Ensemble combines all configured real providers,
+        // the ones that cannot be created (e.g. without API key) are skipped
+        let members = if provider.is_real() {
+            Vec::new()
+        } else {
+            self.get_configured_providers()?
+                .into_iter()
+                .map(|member| (member, config.weights.get(&member).copied().unwrap_or(1.0)))
+                .filter(|(_, weight)| *weight > 0.0)
+                .filter_map(|(member, weight)| match self.get_provider(member) {
+                    Ok(member) => Some((member, weight)),
+                    Err(error) => {
+                        eprintln!("Provider of ensemble is skipped, {member:?}: {error}");
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        // Environment variable takes precedence over the base URL from config
+        let base_url = env::var(base_url_env_var(provider))
+            .ok()
+            .or_else(|| config.base_urls.get(&provider).cloned());
+
+        Ok(ProviderIR {
+            key,
+            contact,
+            provider,
+            base_url,
+            members,
+        }
+        .into())
     }
 
     /// Get default provider
@@ -62,4 +129,48 @@ pub trait ProviderLoader {
 
         self.get_provider(default_provider)
     }
+
+    /// Get types of real providers the user has configured, ordered by name:
+    /// the ones with API keys and the keyless ones that are configured, default,
+    /// in priority or weighted (they would send requests without being asked to otherwise)
+    fn get_configured_providers(&self) -> Result<Vec<ProviderType>> {
+        let config = self.get_config()?;
+
+        let mut provider_types = PROVIDER_TYPE_MAP
+            .values()
+            .copied()
+            .filter(|provider| provider.is_real())
+            .filter(|provider| {
+                config.keys.contains_key(provider)
+                    || config.default == Some(*provider)
+                    || config.priority.contains(provider)
+                    || config.weights.contains_key(provider)
+            })
+            .collect::<Vec<_>>();
+        provider_types.sort_by_key(|provider| format!("{provider:?}"));
+
+        Ok(provider_types)
+    }
+
+    /// Get types of providers to try in order, the default one goes first
+    fn get_provider_chain(&self) -> Result<Vec<ProviderType>> {
+        let config = self.get_config()?;
+        let mut chain = vec![config.default.ok_or(LoaderError::DefaultProviderNotSet)?];
+
+        for provider in &config.priority {
+            if !chain.contains(provider) {
+                chain.push(*provider);
+            }
+        }
+
+        Ok(chain)
+    }
+}
+
+/// Name of environment variable that overrides base URL of provider (e.g. WEATHER_OPENWEATHER_BASE_URL)
+pub fn base_url_env_var(provider: ProviderType) -> String {
+    format!(
+        "WEATHER_{}_BASE_URL",
+        format!("{provider:?}").to_uppercase()
+    )
 }
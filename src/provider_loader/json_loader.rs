@@ -6,7 +6,7 @@ use std::{
 use thiserror::Error;
 
 use super::ProviderLoader;
-use crate::{config::Config, provider::ProviderType};
+use crate::{config::Config, location::Location, provider::ProviderType, units::Units};
 
 /// Errors for JsonLoader
 #[derive(Error, Debug)]
@@ -28,6 +28,18 @@ pub enum JsonLoaderError {
     FailedToSetDefaultProvider { provider: ProviderType },
     #[error("Failed to set API key for provider {provider:?}")]
     FailedToSetAPI { provider: ProviderType },
+    #[error("Failed to set contact for provider {provider:?}")]
+    FailedToSetContact { provider: ProviderType },
+    #[error("Failed to set base URL for provider {provider:?}")]
+    FailedToSetBaseUrl { provider: ProviderType },
+    #[error("Failed to set unit system {units:?}")]
+    FailedToSetUnits { units: Units },
+    #[error("Failed to change location {name}")]
+    FailedToChangeLocation { name: String },
+    #[error("Failed to set provider priority {priority:?}")]
+    FailedToSetPriority { priority: Vec<ProviderType> },
+    #[error("Failed to set weight for provider {provider:?}")]
+    FailedToSetWeight { provider: ProviderType },
 }
 
 /// Loader for saving config and loading providers from JSON
@@ -126,15 +138,222 @@ impl ProviderLoader for JsonLoader {
 
         Ok(())
     }
+
+    fn set_provider_contact(&mut self, provider: ProviderType, contact: &str) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToSetContact { provider })?;
+
+        config.contacts.insert(provider, contact.to_owned());
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn set_provider_base_url(&mut self, provider: ProviderType, base_url: &str) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToSetBaseUrl { provider })?;
+
+        // Empty base URL resets provider to its default one
+        if base_url.is_empty() {
+            config.base_urls.remove(&provider);
+        } else {
+            config.base_urls.insert(provider, base_url.to_owned());
+        }
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn set_units(&mut self, units: Units) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToSetUnits { units })?;
+
+        config.units = units;
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn add_location(&mut self, name: &str, location: Location) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToChangeLocation {
+                name: name.to_owned(),
+            })?;
+
+        config.locations.insert(name.to_owned(), location);
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn remove_location(&mut self, name: &str) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToChangeLocation {
+                name: name.to_owned(),
+            })?;
+
+        config.locations.remove(name);
+        if config.default_location.as_deref() == Some(name) {
+            config.default_location = None;
+        }
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn set_default_location(&mut self, name: Option<&str>) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToChangeLocation {
+                name: name.unwrap_or_default().to_owned(),
+            })?;
+
+        config.default_location = name.map(str::to_owned);
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn set_priority(&mut self, priority: Vec<ProviderType>) -> Result<()> {
+        let mut config =
+            self.config
+                .take()
+                .ok_or_else(|| JsonLoaderError::FailedToSetPriority {
+                    priority: priority.clone(),
+                })?;
+
+        config.priority = priority;
+
+        self.config = Some(config);
+
+        Ok(())
+    }
+
+    fn set_weight(&mut self, provider: ProviderType, weight: f64) -> Result<()> {
+        let mut config = self
+            .config
+            .take()
+            .ok_or(JsonLoaderError::FailedToSetWeight { provider })?;
+
+        config.weights.insert(provider, weight);
+
+        self.config = Some(config);
+
+        Ok(())
+    }
 }
 
 impl JsonLoader {
     /// Create and save blank config
     fn create_config_file(&self) -> Result<()> {
-        let loader: Self = Default::default();
+        let loader = JsonLoader {
+            path: self.path.to_owned(),
+            config: Some(Default::default()),
+        };
 
         loader.save_config()?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_loader::LoaderError;
+
+    fn with_config(config: Config) -> JsonLoader {
+        JsonLoader {
+            path: String::new(),
+            config: Some(config),
+        }
+    }
+
+    #[test]
+    fn keyless_providers_are_configured_only_if_chosen() {
+        let loader = with_config(Config {
+            keys: [(ProviderType::WeatherApi, "key".to_owned())].into(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            loader.get_configured_providers().unwrap(),
+            [ProviderType::WeatherApi]
+        );
+
+        let loader = with_config(Config {
+            default: Some(ProviderType::Ensemble),
+            keys: [
+                (ProviderType::WeatherApi, "key".to_owned()),
+                (ProviderType::OpenMeteo, String::new()),
+            ]
+            .into(),
+            priority: vec![ProviderType::Nws],
+            weights: [(ProviderType::MetNorway, 0.5)].into(),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            loader.get_configured_providers().unwrap(),
+            [
+                ProviderType::MetNorway,
+                ProviderType::Nws,
+                ProviderType::OpenMeteo,
+                ProviderType::WeatherApi
+            ]
+        );
+    }
+
+    #[test]
+    fn contact_is_required_for_providers_identifying_user() {
+        let loader = with_config(Config {
+            keys: [(ProviderType::Nws, String::new())].into(),
+            ..Default::default()
+        });
+
+        let error = loader.get_provider(ProviderType::Nws).err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<LoaderError>(),
+            Some(LoaderError::ContactNotFound {
+                provider: ProviderType::Nws
+            })
+        ));
+        assert!(loader.get_provider(ProviderType::OpenMeteo).is_ok());
+
+        let loader = with_config(Config {
+            contacts: [(ProviderType::Nws, "me@example.com".to_owned())].into(),
+            ..Default::default()
+        });
+
+        assert!(loader.get_provider(ProviderType::Nws).is_ok());
+    }
+
+    #[test]
+    fn ensemble_skips_members_that_cannot_be_created() {
+        let loader = with_config(Config {
+            default: Some(ProviderType::Ensemble),
+            weights: [(ProviderType::TomorrowIo, 1.0)].into(),
+            ..Default::default()
+        });
+
+        assert!(loader.get_provider(ProviderType::TomorrowIo).is_err());
+        assert!(loader.get_provider(ProviderType::Ensemble).is_ok());
+    }
+}
@@ -0,0 +1,327 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs::{create_dir_all, read_to_string, remove_file, write},
+    path::PathBuf,
+};
+use thiserror::Error;
+
+use crate::{
+    cache::{cache_dir, fnv1a, json_files},
+    forecast::Forecast,
+    location::Location,
+    provider::ProviderType,
+};
+
+/// Errors for ForecastStore
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("Forecast for {location} on {date} is not stored")]
+    ForecastNotStored { location: String, date: String },
+    #[error("Failed to write stored forecasts {path} with error '{error}'")]
+    FailedToWrite { error: std::io::Error, path: String },
+    #[error("Failed to stringify stored forecasts with error '{error}'")]
+    FailedToStringify { error: serde_json::Error },
+    #[error("Failed to read stored forecasts {path} with error '{error}'")]
+    FailedToRead { error: std::io::Error, path: String },
+    #[error("Failed to remove stored forecasts {path} with error '{error}'")]
+    FailedToRemove { error: std::io::Error, path: String },
+}
+
+/// Days whose forecasts are not updated for this number of days are removed
+const MAX_AGE_DAYS: i64 = 30;
+
+/// Kind of forecasts stored for the day
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StoredKind {
+    Current,
+    Daily,
+    Hourly,
+}
+
+/// Provider and time the stored forecasts were fetched
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Origin {
+    pub provider: ProviderType,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Pretty print for Origin, e.g. "OpenWeather 3 hours ago"
+impl Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let age = Utc::now() - self.fetched_at;
+
+        let (value, unit) = if age.num_days() > 0 {
+            (age.num_days(), "day")
+        } else if age.num_hours() > 0 {
+            (age.num_hours(), "hour")
+        } else {
+            (age.num_minutes(), "minute")
+        };
+        let plural = if value == 1 { "" } else { "s" };
+
+        write!(f, "{:?} {value} {unit}{plural} ago", self.provider)
+    }
+}
+
+/// Forecasts in metric units with their origin
+#[derive(Serialize, Deserialize)]
+pub struct Stored {
+    pub origin: Origin,
+    pub forecasts: Vec<Forecast>,
+}
+
+/// All stored forecasts for the location on the date
+#[derive(Serialize, Deserialize)]
+struct StoredDay {
+    location: Location,
+    date: NaiveDate,
+    current: Option<Stored>,
+    daily: Option<Stored>,
+    hourly: Option<Stored>,
+}
+
+impl StoredDay {
+    /// Time the last of its forecasts was fetched, none => nothing is stored
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        [&self.current, &self.daily, &self.hourly]
+            .into_iter()
+            .filter_map(|stored| Some(stored.as_ref()?.origin.fetched_at))
+            .max()
+    }
+
+    fn slot(&mut self, kind: StoredKind) -> &mut Option<Stored> {
+        match kind {
+            StoredKind::Current => &mut self.current,
+            StoredKind::Daily => &mut self.daily,
+            StoredKind::Hourly => &mut self.hourly,
+        }
+    }
+}
+
+/// Last known forecasts for each location and date, kept regardless of their age
+pub struct ForecastStore {
+    dir: PathBuf,
+}
+
+impl ForecastStore {
+    /// Open store in the cache directory
+    pub fn new() -> Result<Self> {
+        Ok(ForecastStore {
+            dir: cache_dir()?.join("forecasts"),
+        })
+    }
+
+    /// Most recent forecasts of the kind for the location on the date
+    pub fn load(&self, location: &Location, date: &NaiveDate, kind: StoredKind) -> Option<Stored> {
+        self.read(location, date)?.slot(kind).take()
+    }
+
+    /// Replace forecasts of the kind for the location on the date
+    pub fn save(
+        &self,
+        location: &Location,
+        date: &NaiveDate,
+        kind: StoredKind,
+        stored: Stored,
+    ) -> Result<()> {
+        let mut day = self.read(location, date).unwrap_or(StoredDay {
+            location: location.clone(),
+            date: *date,
+            current: None,
+            daily: None,
+            hourly: None,
+        });
+        *day.slot(kind) = Some(stored);
+
+        create_dir_all(&self.dir).map_err(|error| StoreError::FailedToWrite {
+            error,
+            path: self.dir.display().to_string(),
+        })?;
+
+        let raw_day =
+            serde_json::to_string(&day).map_err(|error| StoreError::FailedToStringify { error })?;
+
+        let path = self.path(location, date);
+        write(&path, raw_day).map_err(|error| StoreError::FailedToWrite {
+            error,
+            path: path.display().to_string(),
+        })?;
+
+        Ok(())
+    }
+
+    /// Remove days that are not updated for long (and broken ones), returns their number
+    pub fn prune(&self) -> Result<usize> {
+        let oldest = Utc::now() - Duration::days(MAX_AGE_DAYS);
+
+        self.remove_days(|raw_day| {
+            serde_json::from_str::<StoredDay>(raw_day)
+                .ok()
+                .and_then(|day| day.updated_at())
+                .map_or(true, |updated_at| updated_at < oldest)
+        })
+    }
+
+    /// Remove all stored days, returns their number
+    pub fn clear(&self) -> Result<usize> {
+        self.remove_days(|_| true)
+    }
+
+    /// Remove stored days matching the condition on their raw content
+    fn remove_days(&self, condition: impl Fn(&str) -> bool) -> Result<usize> {
+        let paths = json_files(&self.dir).map_err(|error| StoreError::FailedToRead {
+            error,
+            path: self.dir.display().to_string(),
+        })?;
+
+        let mut removed = 0;
+
+        for path in paths {
+            // File may be removed by another process meanwhile
+            let Ok(raw_day) = read_to_string(&path) else {
+                continue;
+            };
+            if !condition(&raw_day) {
+                continue;
+            }
+
+            remove_file(&path).map_err(|error| StoreError::FailedToRemove {
+                error,
+                path: path.display().to_string(),
+            })?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Stored day, broken file is treated as missing
+    fn read(&self, location: &Location, date: &NaiveDate) -> Option<StoredDay> {
+        let raw_day = read_to_string(self.path(location, date)).ok()?;
+
+        // Different days can share the file in case of hash collision
+        serde_json::from_str::<StoredDay>(&raw_day)
+            .ok()
+            .filter(|day| day.location == *location && day.date == *date)
+    }
+
+    /// File of the day, named by hash of the location and date
+    fn path(&self, location: &Location, date: &NaiveDate) -> PathBuf {
+        let location = serde_json::to_string(location).unwrap_or_default();
+        let hash = fnv1a(format!("{location} {date}").as_bytes());
+
+        self.dir.join(format!("{hash:016x}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_dir_all;
+
+    use super::*;
+
+    /// Store in its own temporary directory
+    fn store(name: &str) -> ForecastStore {
+        let dir = std::env::temp_dir().join(format!("weather-store-{}-{name}", std::process::id()));
+        let _ = remove_dir_all(&dir);
+
+        ForecastStore { dir }
+    }
+
+    fn stored(age: Duration) -> Stored {
+        Stored {
+            origin: Origin {
+                provider: ProviderType::OpenMeteo,
+                fetched_at: Utc::now() - age,
+            },
+            forecasts: vec![Forecast::default()],
+        }
+    }
+
+    fn location() -> Location {
+        Location::Coordinates {
+            lat: 59.91,
+            lon: 10.75,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn prune_removes_old_and_broken_days() {
+        let store = store("prune");
+        let location = location();
+
+        store
+            .save(
+                &location,
+                &date(1),
+                StoredKind::Daily,
+                stored(Duration::days(40)),
+            )
+            .unwrap();
+        store
+            .save(
+                &location,
+                &date(2),
+                StoredKind::Daily,
+                stored(Duration::days(40)),
+            )
+            .unwrap();
+        // Recent hourly forecast keeps the whole day
+        store
+            .save(
+                &location,
+                &date(2),
+                StoredKind::Hourly,
+                stored(Duration::hours(1)),
+            )
+            .unwrap();
+        store
+            .save(
+                &location,
+                &date(3),
+                StoredKind::Daily,
+                stored(Duration::days(1)),
+            )
+            .unwrap();
+        write(store.dir.join("broken.json"), "{").unwrap();
+
+        assert_eq!(store.prune().unwrap(), 2);
+        assert!(store.load(&location, &date(1), StoredKind::Daily).is_none());
+        assert!(store.load(&location, &date(2), StoredKind::Daily).is_some());
+        assert!(store.load(&location, &date(3), StoredKind::Daily).is_some());
+
+        remove_dir_all(&store.dir).unwrap();
+    }
+
+    #[test]
+    fn clear_removes_all_days() {
+        let store = store("clear");
+        let location = location();
+
+        // Missing directory has no days
+        assert_eq!(store.clear().unwrap(), 0);
+
+        for day in 1..=3 {
+            store
+                .save(
+                    &location,
+                    &date(day),
+                    StoredKind::Daily,
+                    stored(Duration::zero()),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(store.clear().unwrap(), 3);
+        assert!(store.load(&location, &date(1), StoredKind::Daily).is_none());
+
+        remove_dir_all(&store.dir).unwrap();
+    }
+}
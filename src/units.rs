@@ -0,0 +1,131 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Unit systems of forecast values
+#[derive(ValueEnum, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    /// °C, m/s, hPa, mm
+    #[default]
+    Metric,
+    /// °F, mph, hPa, in
+    Imperial,
+    /// K, m/s, hPa, mm
+    Standard,
+}
+
+impl Units {
+    /// Convert temperature from °C
+    pub fn temperature(self, celsius: f64) -> f64 {
+        round(match self {
+            Units::Metric => celsius,
+            Units::Imperial => celsius * 9.0 / 5.0 + 32.0,
+            Units::Standard => celsius + 273.15,
+        })
+    }
+
+    /// Convert difference of temperatures from °C
+    pub fn temperature_difference(self, celsius: f64) -> f64 {
+        round(match self {
+            Units::Metric | Units::Standard => celsius,
+            Units::Imperial => celsius * 9.0 / 5.0,
+        })
+    }
+
+    /// Convert speed from m/s
+    pub fn speed(self, mps: f64) -> f64 {
+        round(match self {
+            Units::Metric | Units::Standard => mps,
+            Units::Imperial => mps * 2.236_936,
+        })
+    }
+
+    /// Convert precipitation from mm
+    pub fn precipitation(self, mm: f64) -> f64 {
+        round(match self {
+            Units::Metric | Units::Standard => mm,
+            Units::Imperial => mm / 25.4,
+        })
+    }
+
+    pub fn temperature_label(self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+            Units::Standard => "K",
+        }
+    }
+
+    pub fn speed_label(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Standard => "m/s",
+            Units::Imperial => "mph",
+        }
+    }
+
+    pub fn precipitation_label(self) -> &'static str {
+        match self {
+            Units::Metric | Units::Standard => "mm",
+            Units::Imperial => "in",
+        }
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperatures() {
+        let cases = [
+            (Units::Metric, 20.0, 20.0),
+            (Units::Imperial, 20.0, 68.0),
+            (Units::Imperial, 0.0, 32.0),
+            (Units::Imperial, -40.0, -40.0),
+            (Units::Imperial, 36.6, 97.88),
+            (Units::Standard, 0.0, 273.15),
+            (Units::Standard, -273.15, 0.0),
+        ];
+
+        for (units, celsius, expected) in cases {
+            assert_eq!(units.temperature(celsius), expected, "{units:?} {celsius}");
+        }
+
+        // Difference has no offset of the scale
+        assert_eq!(Units::Imperial.temperature_difference(10.0), 18.0);
+        assert_eq!(Units::Standard.temperature_difference(10.0), 10.0);
+    }
+
+    #[test]
+    fn speeds_and_precipitation() {
+        let cases = [
+            (Units::Metric, 10.0, 10.0, 25.4, 25.4),
+            (Units::Standard, 10.0, 10.0, 25.4, 25.4),
+            (Units::Imperial, 10.0, 22.37, 25.4, 1.0),
+            (Units::Imperial, 4.47, 10.0, 2.5, 0.1),
+        ];
+
+        for (units, mps, speed, mm, precipitation) in cases {
+            assert_eq!(units.speed(mps), speed, "{units:?} {mps} m/s");
+            assert_eq!(units.precipitation(mm), precipitation, "{units:?} {mm} mm");
+        }
+    }
+
+    #[test]
+    fn imperial_round_trip() {
+        // Values converted back are off only by rounding to hundredths
+        for value in [-30.0, -0.5, 0.0, 7.77, 15.3, 42.0] {
+            let celsius = (Units::Imperial.temperature(value) - 32.0) * 5.0 / 9.0;
+            let mps = Units::Imperial.speed(value.abs()) / 2.236_936;
+            let mm = Units::Imperial.precipitation(value.abs()) * 25.4;
+
+            assert!((celsius - value).abs() < 0.01, "temperature {value}");
+            assert!((mps - value.abs()).abs() < 0.01, "speed {value}");
+            assert!((mm - value.abs()).abs() < 0.13, "precipitation {value}");
+        }
+    }
+}